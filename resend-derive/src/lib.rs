@@ -0,0 +1,105 @@
+// ABOUTME: Derive macros for the Resend CLI.
+// ABOUTME: Generates Tabular impls (table headers and rows) from struct fields.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitInt, LitStr};
+
+/// Derive `Tabular` for a struct with named fields
+///
+/// Each field becomes a column, in declaration order, rendered through the
+/// `Cell` trait. Field attributes:
+///
+/// - `#[tabular(name = "CREATED")]` sets the header (default: the field name
+///   upper-cased, underscores as spaces)
+/// - `#[tabular(order = 5)]` moves the column; unordered fields use their
+///   declaration index
+/// - `#[tabular(default = "sent")]` is shown when the value is missing
+/// - `#[tabular(skip)]` leaves the field out of the table
+#[proc_macro_derive(Tabular, attributes(tabular))]
+pub fn derive_tabular(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct Column {
+    ident: syn::Ident,
+    name: String,
+    order: usize,
+    default: Option<String>,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "Tabular can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "Tabular needs a struct with named fields",
+        ));
+    };
+
+    let mut columns = Vec::new();
+    for (index, field) in fields.named.iter().enumerate() {
+        let ident = field.ident.clone().expect("named field");
+        let mut column = Column {
+            name: ident.to_string().to_uppercase().replace('_', " "),
+            ident,
+            order: index,
+            default: None,
+        };
+        let mut skip = false;
+
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("tabular")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                } else if meta.path.is_ident("name") {
+                    column.name = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("order") {
+                    column.order = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                } else if meta.path.is_ident("default") {
+                    column.default = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else {
+                    return Err(meta.error("expected `name`, `order`, `default` or `skip`"));
+                }
+                Ok(())
+            })?;
+        }
+
+        if !skip {
+            columns.push(column);
+        }
+    }
+    columns.sort_by_key(|c| c.order);
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let headers = columns.iter().map(|c| &c.name);
+    let cells = columns.iter().map(|c| {
+        let ident = &c.ident;
+        let default = c.default.as_deref().unwrap_or_default();
+        quote! {
+            crate::types::Cell::cell(&self.#ident).unwrap_or_else(|| #default.to_string())
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics crate::types::Tabular for #name #ty_generics #where_clause {
+            fn headers() -> Vec<&'static str> {
+                vec![#(#headers),*]
+            }
+
+            fn row(&self) -> Vec<String> {
+                vec![#(#cells),*]
+            }
+        }
+    })
+}
@@ -0,0 +1,102 @@
+// ABOUTME: Checkpoints for resuming interrupted batch sends.
+// ABOUTME: Records how many input entries were sent, tied to a hash of the input file.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How far a batch send got through its input file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Checkpoint {
+    #[serde(skip)]
+    path: PathBuf,
+    /// SHA-256 of the input file the progress refers to
+    pub file_hash: String,
+    /// Number of leading input entries already handled
+    pub sent: usize,
+}
+
+impl Checkpoint {
+    /// Load the checkpoint at `path` for `input`, starting at zero if there
+    /// is none yet
+    ///
+    /// Fails if the checkpoint was written for different file contents, since
+    /// its entry count would no longer line up.
+    pub fn load(path: &Path, input: &Path) -> Result<Self> {
+        let file_hash = file_hash(input)?;
+        if !path.exists() {
+            return Ok(Self {
+                path: path.to_path_buf(),
+                file_hash,
+                sent: 0,
+            });
+        }
+
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read checkpoint: {}", path.display()))?;
+        let saved: Checkpoint = serde_json::from_str(&contents)
+            .with_context(|| format!("Checkpoint file is not valid: {}", path.display()))?;
+        if saved.file_hash != file_hash {
+            bail!(
+                "{} has changed since checkpoint {} was written; delete the checkpoint to start over",
+                input.display(),
+                path.display()
+            );
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            ..saved
+        })
+    }
+
+    /// Record that the first `sent` entries are done and save
+    pub fn record(&mut self, sent: usize) -> Result<()> {
+        self.sent = sent;
+        let contents = serde_json::to_string(self)?;
+        fs::write(&self.path, contents)
+            .with_context(|| format!("Failed to write checkpoint: {}", self.path.display()))
+    }
+}
+
+/// Hex SHA-256 of a file's contents
+fn file_hash(path: &Path) -> Result<String> {
+    let contents = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let digest = Sha256::digest(&contents);
+    Ok(digest.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("batch.json");
+        let path = dir.path().join("batch.checkpoint");
+        fs::write(&input, "[1, 2, 3]").unwrap();
+
+        let mut checkpoint = Checkpoint::load(&path, &input).unwrap();
+        assert_eq!(checkpoint.sent, 0);
+        checkpoint.record(2).unwrap();
+
+        let checkpoint = Checkpoint::load(&path, &input).unwrap();
+        assert_eq!(checkpoint.sent, 2);
+    }
+
+    #[test]
+    fn test_checkpoint_refuses_changed_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("batch.json");
+        let path = dir.path().join("batch.checkpoint");
+        fs::write(&input, "[1, 2, 3]").unwrap();
+        Checkpoint::load(&path, &input).unwrap().record(1).unwrap();
+
+        fs::write(&input, "[4, 2, 3]").unwrap();
+        let err = Checkpoint::load(&path, &input).unwrap_err();
+        assert!(err.to_string().contains("has changed since checkpoint"));
+    }
+}
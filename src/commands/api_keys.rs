@@ -1,12 +1,16 @@
 // ABOUTME: API key management commands.
 // ABOUTME: Create, list, and delete API keys.
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use clap::Subcommand;
-
-use crate::client::ResendClient;
-use crate::commands::{build_config, require_valid_config, CommonArgs};
-use crate::formatters::format_and_output;
+use std::path::PathBuf;
+
+use crate::commands::{
+    build_client, build_config, confirm_production_delete, production_banner, require_valid_config,
+    CommonArgs, DeleteArgs,
+};
+use crate::config::write_private;
+use crate::formatters::{format_and_output, output_result};
 use crate::types::CreateApiKeyRequest;
 
 #[derive(Debug, Subcommand)]
@@ -24,6 +28,10 @@ pub enum ApiKeysCommands {
         #[arg(long)]
         domain_id: Option<String>,
 
+        /// Write the new token to this file (mode 0600) instead of printing it
+        #[arg(long, value_name = "PATH")]
+        save_token: Option<PathBuf>,
+
         #[command(flatten)]
         common: CommonArgs,
     },
@@ -39,24 +47,38 @@ pub enum ApiKeysCommands {
         /// API key ID
         id: String,
 
+        #[command(flatten)]
+        delete: DeleteArgs,
+
         #[command(flatten)]
         common: CommonArgs,
     },
 }
 
 impl ApiKeysCommands {
+    /// Common arguments of the chosen subcommand
+    pub fn common_mut(&mut self) -> &mut CommonArgs {
+        match self {
+            ApiKeysCommands::Create { common, .. }
+            | ApiKeysCommands::List { common, .. }
+            | ApiKeysCommands::Delete { common, .. } => common,
+        }
+    }
+
     pub async fn execute(&self) -> Result<()> {
         match self {
             ApiKeysCommands::Create {
                 name,
                 permission,
                 domain_id,
+                save_token,
                 common,
             } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
+                production_banner(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
 
                 let req = CreateApiKeyRequest {
                     name: name.clone(),
@@ -64,43 +86,64 @@ impl ApiKeysCommands {
                     domain_id: domain_id.clone(),
                 };
 
-                let api_key = client.create_api_key(req).await?;
+                let mut api_key = client.create_api_key(req).await?;
+
+                let saved_to = match save_token {
+                    Some(path) => {
+                        let Some(token) = api_key.token.take() else {
+                            bail!(
+                                "API key {} was created but the API returned no token to save",
+                                api_key.id
+                            );
+                        };
+                        write_private(path, &token).with_context(|| {
+                            format!("Failed to save token to {}", path.display())
+                        })?;
+                        Some(path)
+                    }
+                    None => None,
+                };
 
-                if common.json {
-                    println!("{}", serde_json::to_string_pretty(&api_key)?);
-                } else {
-                    println!("API key created successfully!");
-                    println!("ID: {}", api_key.id);
-                    println!("Name: {}", api_key.name);
+                let message = {
+                    let mut lines = vec![
+                        "API key created successfully!".to_string(),
+                        format!("ID: {}", api_key.id),
+                        format!("Name: {}", api_key.name),
+                    ];
                     if let Some(token) = &api_key.token {
-                        println!();
-                        println!("Token: {}", token);
-                        println!();
-                        println!("Save this token - it won't be shown again!");
+                        lines.push(String::new());
+                        lines.push(format!("Token: {}", token));
+                        lines.push(String::new());
+                        lines.push("Save this token - it won't be shown again!".to_string());
                     }
-                }
+                    if let Some(path) = saved_to {
+                        lines.push(format!("Token saved to {}", path.display()));
+                    }
+                    lines.join("\n")
+                };
 
-                Ok(())
+                output_result(&api_key, message, &config.output_options())
             }
 
             ApiKeysCommands::List { common } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
                 let api_keys = client.list_api_keys().await?;
 
-                format_and_output(&api_keys, config.format, config.output.as_deref())
+                format_and_output(&api_keys, &config.output_options())
             }
 
-            ApiKeysCommands::Delete { id, common } => {
+            ApiKeysCommands::Delete { id, delete, common } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
+                confirm_production_delete(&config, &format!("API key {id}"), delete)?;
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
                 client.delete_api_key(id).await?;
 
-                if !common.json {
+                if common.show_messages() {
                     println!("API key deleted successfully!");
                 }
 
@@ -0,0 +1,120 @@
+// ABOUTME: Audience management commands.
+// ABOUTME: Create, list, get, and delete audiences (contact lists).
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use crate::commands::{
+    build_client, build_config, confirm_production_delete, production_banner, require_valid_config,
+    CommonArgs, DeleteArgs,
+};
+use crate::formatters::{format_and_output, format_and_output_single, output_result};
+use crate::types::CreateAudienceRequest;
+
+#[derive(Debug, Subcommand)]
+pub enum AudiencesCommands {
+    /// Create an audience
+    Create {
+        /// Audience name
+        name: String,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// List all audiences
+    List {
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Get an audience by ID
+    Get {
+        /// Audience ID
+        id: String,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Delete an audience
+    Delete {
+        /// Audience ID
+        id: String,
+
+        #[command(flatten)]
+        delete: DeleteArgs,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+}
+
+impl AudiencesCommands {
+    /// Common arguments of the chosen subcommand
+    pub fn common_mut(&mut self) -> &mut CommonArgs {
+        match self {
+            AudiencesCommands::Create { common, .. }
+            | AudiencesCommands::List { common, .. }
+            | AudiencesCommands::Get { common, .. }
+            | AudiencesCommands::Delete { common, .. } => common,
+        }
+    }
+
+    pub async fn execute(&self) -> Result<()> {
+        match self {
+            AudiencesCommands::Create { name, common } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                production_banner(&config);
+
+                let client = build_client(&config)?;
+
+                let req = CreateAudienceRequest { name: name.clone() };
+                let audience = client.create_audience(req).await?;
+
+                let message = format!(
+                    "Audience created successfully!\nID: {}\nName: {}",
+                    audience.id, audience.name
+                );
+
+                output_result(&audience, message, &config.output_options())
+            }
+
+            AudiencesCommands::List { common } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+
+                let client = build_client(&config)?;
+                let audiences = client.list_audiences().await?;
+
+                format_and_output(&audiences, &config.output_options())
+            }
+
+            AudiencesCommands::Get { id, common } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+
+                let client = build_client(&config)?;
+                let audience = client.get_audience(id).await?;
+
+                format_and_output_single(&audience, &config.output_options())
+            }
+
+            AudiencesCommands::Delete { id, delete, common } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                confirm_production_delete(&config, &format!("audience {id}"), delete)?;
+
+                let client = build_client(&config)?;
+                client.delete_audience(id).await?;
+
+                if common.show_messages() {
+                    println!("Audience deleted successfully!");
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
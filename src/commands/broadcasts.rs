@@ -0,0 +1,205 @@
+// ABOUTME: Broadcast management commands.
+// ABOUTME: Create, list, get, send, and delete broadcasts to an audience.
+
+use anyhow::{bail, Result};
+use clap::Subcommand;
+
+use crate::commands::emails::parse_scheduled_at;
+use crate::commands::{
+    build_client, build_config, confirm_production_delete, production_banner, require_valid_config,
+    CommonArgs, DeleteArgs,
+};
+use crate::formatters::{format_and_output, format_and_output_single, output_result};
+use crate::types::{CreateBroadcastRequest, SendBroadcastRequest};
+
+#[derive(Debug, Subcommand)]
+pub enum BroadcastsCommands {
+    /// Create a broadcast (sent later with `broadcasts send`)
+    Create {
+        /// Audience to send to
+        #[arg(long)]
+        audience_id: String,
+
+        /// Sender email address
+        #[arg(long)]
+        from: String,
+
+        /// Email subject
+        #[arg(long)]
+        subject: String,
+
+        /// Name to show in the dashboard
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Reply-to addresses
+        #[arg(long)]
+        reply_to: Option<Vec<String>>,
+
+        /// HTML content
+        #[arg(long)]
+        html: Option<String>,
+
+        /// Plain text content
+        #[arg(long)]
+        text: Option<String>,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// List all broadcasts
+    List {
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Get a broadcast by ID
+    Get {
+        /// Broadcast ID
+        id: String,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Send a broadcast to its audience
+    Send {
+        /// Broadcast ID
+        id: String,
+
+        /// Send later: ISO 8601, or e.g. "in 2 hours", "+30m", "tomorrow 9am"
+        #[arg(long, value_name = "WHEN", value_parser = parse_scheduled_at)]
+        scheduled_at: Option<String>,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Delete a broadcast that has not been sent
+    Delete {
+        /// Broadcast ID
+        id: String,
+
+        #[command(flatten)]
+        delete: DeleteArgs,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+}
+
+impl BroadcastsCommands {
+    /// Common arguments of the chosen subcommand
+    pub fn common_mut(&mut self) -> &mut CommonArgs {
+        match self {
+            BroadcastsCommands::Create { common, .. }
+            | BroadcastsCommands::List { common, .. }
+            | BroadcastsCommands::Get { common, .. }
+            | BroadcastsCommands::Send { common, .. }
+            | BroadcastsCommands::Delete { common, .. } => common,
+        }
+    }
+
+    pub async fn execute(&self) -> Result<()> {
+        match self {
+            BroadcastsCommands::Create {
+                audience_id,
+                from,
+                subject,
+                name,
+                reply_to,
+                html,
+                text,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                production_banner(&config);
+
+                if html.is_none() && text.is_none() {
+                    bail!("A broadcast needs a body. Pass --html or --text.");
+                }
+
+                let client = build_client(&config)?;
+
+                let req = CreateBroadcastRequest {
+                    audience_id: audience_id.clone(),
+                    from: from.clone(),
+                    subject: subject.clone(),
+                    name: name.clone(),
+                    reply_to: reply_to.clone(),
+                    html: html.clone(),
+                    text: text.clone(),
+                };
+                let broadcast = client.create_broadcast(req).await?;
+
+                let message = format!(
+                    "Broadcast created successfully!\nID: {}\n\nSend it with: resend broadcasts send {}",
+                    broadcast.id, broadcast.id
+                );
+
+                output_result(&broadcast, message, &config.output_options())
+            }
+
+            BroadcastsCommands::List { common } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+
+                let client = build_client(&config)?;
+                let broadcasts = client.list_broadcasts().await?;
+
+                format_and_output(&broadcasts, &config.output_options())
+            }
+
+            BroadcastsCommands::Get { id, common } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+
+                let client = build_client(&config)?;
+                let broadcast = client.get_broadcast(id).await?;
+
+                format_and_output_single(&broadcast, &config.output_options())
+            }
+
+            BroadcastsCommands::Send {
+                id,
+                scheduled_at,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                production_banner(&config);
+
+                let client = build_client(&config)?;
+
+                let req = SendBroadcastRequest {
+                    scheduled_at: scheduled_at.clone(),
+                };
+                let broadcast = client.send_broadcast(id, req).await?;
+
+                let message = match scheduled_at {
+                    Some(at) => format!("Broadcast scheduled for {}!\nID: {}", at, broadcast.id),
+                    None => format!("Broadcast sent successfully!\nID: {}", broadcast.id),
+                };
+
+                output_result(&broadcast, message, &config.output_options())
+            }
+
+            BroadcastsCommands::Delete { id, delete, common } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                confirm_production_delete(&config, &format!("broadcast {id}"), delete)?;
+
+                let client = build_client(&config)?;
+                client.delete_broadcast(id).await?;
+
+                if common.show_messages() {
+                    println!("Broadcast deleted successfully!");
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,66 +1,200 @@
 // ABOUTME: Configuration management commands.
-// ABOUTME: Handles setup, show, and list operations for profiles.
+// ABOUTME: Handles setup, show, list, profile management, and permission repair.
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use clap::Subcommand;
-use dialoguer::Password;
+use dialoguer::{Confirm, Password};
+use serde::Serialize;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
 
-use crate::client::ResendClient;
-use crate::config::Config;
+use crate::client::{request_id, ResendClient};
+use crate::commands::GlobalArgs;
+use crate::config::{loose_mode, restrict_permissions, Config, ConfigFile};
+use crate::formatters::{format_and_output, json, output_error, OutputOptions};
+use crate::temp_files::{until_interrupted, write_temp_in};
+use crate::types::{OutputFormat, Tabular};
 
 #[derive(Debug, Subcommand)]
 pub enum ConfigCommands {
     /// Set up a new configuration profile
     Setup {
-        /// Profile name
-        #[arg(long, default_value = "default")]
-        profile: String,
+        /// Profile name [default: default]
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Print only a JSON result object (errors go to stderr as JSON)
+        #[arg(long)]
+        json: bool,
     },
 
     /// Show current configuration
     Show {
         /// Profile name
-        #[arg(long)]
+        #[arg(long, conflicts_with = "all")]
         profile: Option<String>,
+
+        /// Show every configured profile
+        #[arg(long)]
+        all: bool,
+
+        /// Output as JSON (with --all)
+        #[arg(long, requires = "all")]
+        json: bool,
     },
 
     /// List all profiles
     List,
+
+    /// Delete a profile
+    Delete {
+        /// Profile name
+        profile: String,
+
+        /// Delete without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Use a profile when neither --profile nor RESEND_PROFILE is given
+    SetDefault {
+        /// Profile name
+        profile: String,
+    },
+
+    /// Rename a profile, keeping its key and settings
+    Rename {
+        /// Current profile name
+        old: String,
+
+        /// New profile name
+        new: String,
+    },
+
+    /// Open the config file in $VISUAL or $EDITOR
+    Edit,
+
+    /// Make the config file readable only by you (0600)
+    FixPerms {
+        /// Fix without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
 }
 
 impl ConfigCommands {
+    /// Take options given before `config`; ones given after the subcommand win
+    ///
+    /// Only `--profile` and `--json` mean anything here, and only to `setup`
+    /// and `show`.
+    pub fn inherit(&mut self, global: &GlobalArgs) -> Result<()> {
+        if global.format.is_some() || global.output.is_some() || global.verbose {
+            bail!("Only --profile and --json may come before 'config'; put other options after the subcommand");
+        }
+
+        match self {
+            ConfigCommands::Setup { profile, json } => {
+                if profile.is_none() {
+                    profile.clone_from(&global.profile);
+                }
+                *json |= global.json;
+            }
+            ConfigCommands::Show { profile, all, json } => {
+                if *all && global.profile.is_some() {
+                    bail!("--profile can't be combined with 'config show --all'");
+                }
+                if !*all && global.json {
+                    bail!("--json with 'config show' needs --all");
+                }
+                if profile.is_none() {
+                    profile.clone_from(&global.profile);
+                }
+                *json |= global.json;
+            }
+            _ if global.is_set() => {
+                bail!("Options before 'config' only apply to 'config setup' and 'config show'; put them after the subcommand")
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Whether the command asked for JSON, so its errors should be JSON too
+    pub fn json(&self) -> bool {
+        match self {
+            ConfigCommands::Setup { json, .. } | ConfigCommands::Show { json, .. } => *json,
+            _ => false,
+        }
+    }
+
     pub async fn execute(&self) -> Result<()> {
         match self {
-            ConfigCommands::Setup { profile } => setup_config(profile).await,
-            ConfigCommands::Show { profile } => show_config(profile.as_deref()),
+            ConfigCommands::Setup { profile, json } => {
+                setup_config(profile.as_deref().unwrap_or("default"), *json).await
+            }
+            ConfigCommands::Show {
+                profile,
+                all: false,
+                ..
+            } => show_config(profile.as_deref()),
+            ConfigCommands::Show { json, .. } => show_all_profiles(*json),
             ConfigCommands::List => list_profiles(),
+            ConfigCommands::Delete { profile, yes } => delete_profile(profile, *yes),
+            ConfigCommands::SetDefault { profile } => {
+                Config::set_default_profile(profile)?;
+                println!("Default profile is now '{}'.", profile);
+                Ok(())
+            }
+            ConfigCommands::Rename { old, new } => {
+                Config::rename_profile(old, new)?;
+                println!("Renamed profile '{}' to '{}'.", old, new);
+                Ok(())
+            }
+            ConfigCommands::Edit => edit_config().await,
+            ConfigCommands::FixPerms { yes } => fix_permissions(*yes),
         }
     }
 }
 
-async fn setup_config(profile: &str) -> Result<()> {
-    println!("Setting up profile: {}", profile);
-    println!();
+async fn setup_config(profile: &str, json: bool) -> Result<()> {
+    if !json {
+        println!("Setting up profile: {}", profile);
+        println!();
+    }
 
-    let api_key: String = Password::new()
-        .with_prompt("API Key")
-        .interact()?;
+    let api_key: String = Password::new().with_prompt("API Key").interact()?;
 
     if api_key.is_empty() {
-        eprintln!("Error: API key cannot be empty");
-        std::process::exit(1);
+        setup_failed(
+            json,
+            "Error",
+            "invalid_input",
+            "API key cannot be empty",
+            None,
+        );
     }
 
-    println!();
-    println!("Testing connection...");
+    if !json {
+        println!();
+        println!("Testing connection...");
+    }
 
     let client = ResendClient::new(&api_key)?;
     match client.test_connection().await {
-        Ok(_) => println!("Connection successful!"),
-        Err(e) => {
-            eprintln!("Connection failed: {}", e);
-            std::process::exit(1);
+        Ok(_) => {
+            if !json {
+                println!("Connection successful!");
+            }
         }
+        Err(e) => setup_failed(
+            json,
+            "Connection failed",
+            "connection_failed",
+            &e.to_string(),
+            request_id(&e),
+        ),
     }
 
     Config::set_profile(profile, &api_key)?;
@@ -69,14 +203,42 @@ async fn setup_config(profile: &str) -> Result<()> {
         .map(|p| p.display().to_string())
         .unwrap_or_else(|| "unknown".to_string());
 
-    println!();
-    println!("Configuration saved to {}", config_path);
+    if json {
+        let result = serde_json::json!({
+            "profile": profile,
+            "saved_to": config_path,
+            "verified": true
+        });
+        println!("{}", json::format_json(&result, false)?);
+    } else {
+        println!();
+        println!("Configuration saved to {}", config_path);
+    }
 
     Ok(())
 }
 
+/// Report a setup failure in the requested style and exit
+fn setup_failed(
+    json: bool,
+    label: &str,
+    error: &str,
+    message: &str,
+    request_id: Option<&str>,
+) -> ! {
+    if json {
+        output_error(error, message, request_id);
+    } else {
+        eprintln!("{}: {}", label, message);
+        if let Some(id) = request_id {
+            eprintln!("request-id: {}", id);
+        }
+    }
+    std::process::exit(1);
+}
+
 fn show_config(profile: Option<&str>) -> Result<()> {
-    let config = Config::load(profile, None, None, false)?;
+    let config = Config::load(None, None, profile, None, None, false)?;
 
     println!("Profile: {}", config.profile);
     println!(
@@ -95,6 +257,300 @@ fn show_config(profile: Option<&str>) -> Result<()> {
     Ok(())
 }
 
+/// One configured profile as shown by `config show --all`
+#[derive(Debug, Serialize)]
+struct ProfileSummary {
+    name: String,
+    api_key: String,
+    default: bool,
+    redirect_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    environment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    connect_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key_file: Option<PathBuf>,
+}
+
+impl Tabular for ProfileSummary {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "PROFILE",
+            "API KEY",
+            "DEFAULT",
+            "ENVIRONMENT",
+            "REDIRECT TO",
+        ]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.api_key.clone(),
+            if self.default { "*" } else { "" }.to_string(),
+            self.environment.clone().unwrap_or_default(),
+            self.redirect_to.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+/// Summarise every profile in the config file, sorted by name
+fn profile_summaries(config_file: &ConfigFile, default_profile: &str) -> Vec<ProfileSummary> {
+    let mut summaries: Vec<ProfileSummary> = config_file
+        .profiles
+        .iter()
+        .map(|(name, profile)| ProfileSummary {
+            name: name.clone(),
+            api_key: match (&profile.api_key, &profile.api_key_file) {
+                (Some(key), _) => Config::mask_key(key),
+                (None, Some(path)) => format!("(file {})", path.display()),
+                (None, None) => "(not set)".to_string(),
+            },
+            default: name == default_profile,
+            redirect_to: profile.redirect_to.clone(),
+            environment: profile.environment.clone(),
+            base_url: profile.base_url.clone(),
+            timeout: profile.timeout,
+            connect_timeout: profile.connect_timeout,
+            api_key_file: profile.api_key_file.clone(),
+        })
+        .collect();
+    summaries.sort_by(|a, b| a.name.cmp(&b.name));
+    summaries
+}
+
+fn show_all_profiles(json: bool) -> Result<()> {
+    let config_file = Config::load_config_file()?;
+    let summaries = profile_summaries(&config_file, &Config::default_profile_name(&config_file));
+
+    if summaries.is_empty() && !json {
+        println!("No profiles configured.");
+        println!("Run 'resend config setup' to create one.");
+        return Ok(());
+    }
+
+    let options = OutputOptions {
+        format: if json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Table
+        },
+        ..Default::default()
+    };
+    format_and_output(&summaries, &options)
+}
+
+fn fix_permissions(yes: bool) -> Result<()> {
+    let path = Config::config_path()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine config file path"))?;
+
+    if cfg!(not(unix)) {
+        eprintln!(
+            "Warning: file permissions can't be enforced on this platform. \
+             Keep {} where only you can read it.",
+            path.display()
+        );
+        return Ok(());
+    }
+
+    if !path.exists() {
+        println!("No config file at {}", path.display());
+        return Ok(());
+    }
+
+    let Some(mode) = loose_mode(&path)? else {
+        println!("{} is already private (0600).", path.display());
+        return Ok(());
+    };
+
+    eprintln!(
+        "Warning: {} has mode {:o} and can be read by other users. \
+         Any API key stored in it may have been exposed; consider rotating it.",
+        path.display(),
+        mode
+    );
+
+    if !yes {
+        if !std::io::stdin().is_terminal() {
+            bail!("Refusing to change permissions without confirmation. Pass --yes to fix.");
+        }
+        let proceed = Confirm::new()
+            .with_prompt("Set the config file to 0600?")
+            .default(true)
+            .interact()?;
+        if !proceed {
+            bail!("Permissions left unchanged");
+        }
+    }
+
+    restrict_permissions(&path)?;
+    println!("Set {} to 0600.", path.display());
+    Ok(())
+}
+
+fn delete_profile(profile: &str, yes: bool) -> Result<()> {
+    let config_file = Config::load_config_file()?;
+    if !config_file.profiles.contains_key(profile) {
+        bail!("Profile '{}' not found", profile);
+    }
+
+    if profile == Config::default_profile_name(&config_file) {
+        eprintln!(
+            "Warning: '{}' is the profile used when --profile isn't given. \
+             Commands will need --profile or RESEND_API_KEY until you set up another.",
+            profile
+        );
+    }
+
+    if !yes {
+        if !std::io::stdin().is_terminal() {
+            bail!("Refusing to delete a profile without confirmation. Pass --yes to delete.");
+        }
+        let proceed = Confirm::new()
+            .with_prompt(format!("Delete profile '{}'?", profile))
+            .default(false)
+            .interact()?;
+        if !proceed {
+            bail!("Profile left unchanged");
+        }
+    }
+
+    Config::delete_profile(profile)?;
+    println!("Deleted profile '{}'.", profile);
+    Ok(())
+}
+
+/// Starting contents for `config edit` when no config file exists yet
+const CONFIG_TEMPLATE: &str = "\
+# Resend CLI configuration. Keys stored here are kept readable only by you.
+#
+# default_profile: default
+# profiles:
+#   default:
+#     api_key: re_...
+#   staging:
+#     api_key_file: ~/.secrets/resend-staging
+#     redirect_to: qa@example.com
+profiles: {}
+";
+
+/// Edit a copy of the config file, replacing the real one only once the
+/// copy validates, so a mistake never leaves a broken config behind
+async fn edit_config() -> Result<()> {
+    let path = Config::config_path()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine config file path"))?;
+    let dir = path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create config directory: {dir:?}"))?;
+
+    let original = if path.exists() {
+        fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file: {path:?}"))?
+    } else {
+        CONFIG_TEMPLATE.to_string()
+    };
+
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "No editor configured. Set $VISUAL or $EDITOR, or edit {} directly.",
+                path.display()
+            )
+        })?;
+
+    // Created 0600 next to the real file, so persisting it is a rename
+    let draft = write_temp_in(dir, ".config-", ".yml", &original)?;
+    loop {
+        until_interrupted(run_editor(&editor, draft.path())).await?;
+
+        let contents = fs::read_to_string(draft.path())
+            .with_context(|| format!("Failed to read {}", draft.path().display()))?;
+        let Err(err) = validate_config(&contents) else {
+            break;
+        };
+
+        eprintln!("The edited config is not valid:\n{}", err);
+        let again = std::io::stdin().is_terminal()
+            && Confirm::new()
+                .with_prompt("Edit again?")
+                .default(true)
+                .interact()?;
+        if !again {
+            bail!("{} left unchanged", path.display());
+        }
+    }
+
+    draft
+        .persist(&path)
+        .with_context(|| format!("Failed to write config file: {path:?}"))?;
+    println!("Saved {}", path.display());
+    Ok(())
+}
+
+/// Open `path` in the editor and wait for it to exit
+async fn run_editor(editor: &str, path: &Path) -> Result<()> {
+    // Allow editors that need arguments, such as "code --wait"
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .await
+        .with_context(|| format!("Failed to start editor '{}'", editor))?;
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", editor, status);
+    }
+    Ok(())
+}
+
+/// Parse config file contents, pointing at the offending line on a YAML error
+fn validate_config(contents: &str) -> Result<()> {
+    let config_file: ConfigFile = match serde_yaml::from_str(contents) {
+        Ok(config_file) => config_file,
+        Err(err) => bail!("{}", describe_yaml_error(contents, &err)),
+    };
+
+    for (name, profile) in &config_file.profiles {
+        profile.check_timeouts(name)?;
+    }
+    if let Some(name) = &config_file.default_profile {
+        if !config_file.profiles.contains_key(name) {
+            bail!("default_profile '{}' is not a configured profile", name);
+        }
+    }
+    Ok(())
+}
+
+/// A YAML error message followed by the line it refers to and a caret
+fn describe_yaml_error(contents: &str, err: &serde_yaml::Error) -> String {
+    let Some(location) = err.location() else {
+        return err.to_string();
+    };
+    let Some(line) = contents.lines().nth(location.line().saturating_sub(1)) else {
+        return err.to_string();
+    };
+
+    let gutter = location.line().to_string();
+    format!(
+        "{}\n{} | {}\n{} | {}^",
+        err,
+        gutter,
+        line,
+        " ".repeat(gutter.len()),
+        " ".repeat(location.column().saturating_sub(1))
+    )
+}
+
 fn list_profiles() -> Result<()> {
     let profiles = Config::list_profiles()?;
 
@@ -110,3 +566,123 @@ fn list_profiles() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Profile;
+
+    #[test]
+    fn test_inherit_global_profile() {
+        let global = GlobalArgs {
+            profile: Some("prod".to_string()),
+            ..Default::default()
+        };
+
+        let mut show = ConfigCommands::Show {
+            profile: None,
+            all: false,
+            json: false,
+        };
+        show.inherit(&global).unwrap();
+        assert!(matches!(show, ConfigCommands::Show { profile: Some(p), .. } if p == "prod"));
+
+        let mut setup = ConfigCommands::Setup {
+            profile: Some("staging".to_string()),
+            json: false,
+        };
+        setup.inherit(&global).unwrap();
+        assert!(matches!(setup, ConfigCommands::Setup { profile: Some(p), .. } if p == "staging"));
+
+        let mut list = ConfigCommands::List;
+        assert!(list.inherit(&global).is_err());
+        assert!(list.inherit(&GlobalArgs::default()).is_ok());
+    }
+
+    #[test]
+    fn test_config_template_is_valid() {
+        validate_config(CONFIG_TEMPLATE).unwrap();
+    }
+
+    #[test]
+    fn test_validate_config_points_at_yaml_error() {
+        let contents = "profiles:\n  default:\n    api_key: [unclosed\n";
+        let message = validate_config(contents).unwrap_err().to_string();
+        assert!(message.contains("line 3"), "{message}");
+        assert!(message.contains("3 |     api_key: [unclosed"), "{message}");
+    }
+
+    #[test]
+    fn test_validate_config_rejects_unknown_default() {
+        let contents = "default_profile: prod\nprofiles:\n  default: {}\n";
+        let err = validate_config(contents).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "default_profile 'prod' is not a configured profile"
+        );
+    }
+
+    #[test]
+    fn test_profile_summaries_sorted_and_masked() {
+        let mut config_file = ConfigFile::default();
+        config_file.profiles.insert(
+            "staging".to_string(),
+            Profile {
+                api_key: Some("re_staging_key_123".to_string()),
+                redirect_to: Some("qa@example.com".to_string()),
+                ..Default::default()
+            },
+        );
+        config_file
+            .profiles
+            .insert("default".to_string(), Profile::default());
+
+        let summaries = profile_summaries(&config_file, "default");
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].name, "default");
+        assert!(summaries[0].default);
+        assert_eq!(summaries[0].api_key, "(not set)");
+        assert_eq!(summaries[1].api_key, "re_stagi********");
+        assert!(!summaries[1].default);
+        assert_eq!(summaries[1].redirect_to.as_deref(), Some("qa@example.com"));
+    }
+
+    #[test]
+    fn test_profile_summaries_include_connection_settings() {
+        let mut config_file = ConfigFile::default();
+        config_file.profiles.insert(
+            "prod".to_string(),
+            Profile {
+                api_key_file: Some(PathBuf::from("/run/secrets/resend")),
+                environment: Some("production".to_string()),
+                base_url: Some("https://proxy.example.com".to_string()),
+                timeout: Some(60),
+                connect_timeout: Some(5),
+                ..Default::default()
+            },
+        );
+        config_file
+            .profiles
+            .insert("default".to_string(), Profile::default());
+
+        let summaries = profile_summaries(&config_file, "default");
+        let json = serde_json::to_value(&summaries).unwrap();
+        assert_eq!(
+            json[1],
+            serde_json::json!({
+                "name": "prod",
+                "api_key": "(file /run/secrets/resend)",
+                "default": false,
+                "redirect_to": null,
+                "environment": "production",
+                "base_url": "https://proxy.example.com",
+                "timeout": 60,
+                "connect_timeout": 5,
+                "api_key_file": "/run/secrets/resend"
+            })
+        );
+        // Unset settings are left out rather than shown as null
+        assert_eq!(json[0].as_object().unwrap().len(), 4);
+        assert_eq!(summaries[1].row()[3], "production");
+    }
+}
@@ -0,0 +1,539 @@
+// ABOUTME: Contact management commands.
+// ABOUTME: Create, list, get, update, and delete the contacts in an audience.
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Subcommand;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::commands::emails::read_csv_rows;
+use crate::commands::{
+    build_client, build_config, confirm_production_delete, production_banner, require_valid_config,
+    CommonArgs, DeleteArgs,
+};
+use crate::concurrency::run_bounded;
+use crate::formatters::{format_and_output, format_and_output_single, output_result, STDOUT_PATH};
+use crate::types::{Contact, CreateContactRequest, ImportResult, UpdateContactRequest};
+use crate::validation::validate_email;
+
+#[derive(Debug, Subcommand)]
+pub enum ContactsCommands {
+    /// Add a contact to an audience
+    Create {
+        /// Audience ID
+        #[arg(long)]
+        audience_id: String,
+
+        /// Contact email address
+        #[arg(long)]
+        email: String,
+
+        /// First name
+        #[arg(long)]
+        first_name: Option<String>,
+
+        /// Last name
+        #[arg(long)]
+        last_name: Option<String>,
+
+        /// Whether the contact has unsubscribed
+        #[arg(long)]
+        unsubscribed: Option<bool>,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Add contacts to an audience from a CSV file
+    Import {
+        /// Audience ID
+        #[arg(long)]
+        audience_id: String,
+
+        /// CSV with an `email` column and optional `first_name`, `last_name`,
+        /// and `unsubscribed` columns
+        #[arg(long, value_name = "PATH")]
+        file: PathBuf,
+
+        /// Check every row and print a summary without creating anything
+        #[arg(long)]
+        dry_run: bool,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Write every contact in an audience to a CSV file
+    Export {
+        /// Audience ID
+        #[arg(long)]
+        audience_id: String,
+
+        /// CSV file to write, or `-` for stdout (defaults to --output, then stdout)
+        #[arg(long, value_name = "PATH")]
+        file: Option<String>,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// List the contacts in an audience
+    List {
+        /// Audience ID
+        #[arg(long)]
+        audience_id: String,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Get a contact by ID or email address
+    Get {
+        /// Contact ID or email address
+        id: String,
+
+        /// Audience ID
+        #[arg(long)]
+        audience_id: String,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Update a contact
+    Update {
+        /// Contact ID or email address
+        id: String,
+
+        /// Audience ID
+        #[arg(long)]
+        audience_id: String,
+
+        /// New email address
+        #[arg(long)]
+        email: Option<String>,
+
+        /// New first name
+        #[arg(long)]
+        first_name: Option<String>,
+
+        /// New last name
+        #[arg(long)]
+        last_name: Option<String>,
+
+        /// Mark the contact unsubscribed (true) or subscribed again (false)
+        #[arg(long)]
+        unsubscribed: Option<bool>,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Remove a contact from an audience
+    Delete {
+        /// Contact ID or email address
+        id: String,
+
+        /// Audience ID
+        #[arg(long)]
+        audience_id: String,
+
+        #[command(flatten)]
+        delete: DeleteArgs,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+}
+
+impl ContactsCommands {
+    /// Common arguments of the chosen subcommand
+    pub fn common_mut(&mut self) -> &mut CommonArgs {
+        match self {
+            ContactsCommands::Create { common, .. }
+            | ContactsCommands::Import { common, .. }
+            | ContactsCommands::Export { common, .. }
+            | ContactsCommands::List { common, .. }
+            | ContactsCommands::Get { common, .. }
+            | ContactsCommands::Update { common, .. }
+            | ContactsCommands::Delete { common, .. } => common,
+        }
+    }
+
+    pub async fn execute(&self) -> Result<()> {
+        match self {
+            ContactsCommands::Create {
+                audience_id,
+                email,
+                first_name,
+                last_name,
+                unsubscribed,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                production_banner(&config);
+
+                let client = build_client(&config)?;
+
+                let req = CreateContactRequest {
+                    email: email.clone(),
+                    first_name: first_name.clone(),
+                    last_name: last_name.clone(),
+                    unsubscribed: *unsubscribed,
+                };
+                let contact = client.create_contact(audience_id, req).await?;
+
+                let message = format!("Contact created successfully!\nID: {}", contact.id);
+
+                output_result(&contact, message, &config.output_options())
+            }
+
+            ContactsCommands::Import {
+                audience_id,
+                file,
+                dry_run,
+                common,
+            } => {
+                let config = build_config(common)?;
+                let rows: Vec<(u64, String, Result<CreateContactRequest>)> =
+                    read_csv_rows(file, EMAIL_COLUMN)?
+                        .into_iter()
+                        .map(|(line, row)| {
+                            let email = row
+                                .as_ref()
+                                .ok()
+                                .and_then(|row| row.get(EMAIL_COLUMN))
+                                .map(|email| email.trim().to_string())
+                                .unwrap_or_default();
+                            (line, email, row.and_then(|row| contact_from_row(&row)))
+                        })
+                        .collect();
+
+                if *dry_run {
+                    let invalid: Vec<ImportResult> = rows
+                        .iter()
+                        .filter_map(|(line, email, row)| {
+                            row.as_ref().err().map(|e| ImportResult {
+                                line: *line,
+                                email: email.clone(),
+                                id: None,
+                                error: Some(e.to_string()),
+                            })
+                        })
+                        .collect();
+                    let valid = rows.len() - invalid.len();
+
+                    let mut lines: Vec<String> = invalid
+                        .iter()
+                        .map(|r| format!("Line {}: {}", r.line, r.error.as_deref().unwrap_or("")))
+                        .collect();
+                    lines.push(format!(
+                        "Dry run: {} of {} rows valid; nothing imported",
+                        valid,
+                        rows.len()
+                    ));
+                    let summary = json!({"rows": rows.len(), "valid": valid, "invalid": invalid});
+                    output_result(&summary, lines.join("\n"), &config.output_options())?;
+
+                    if !invalid.is_empty() {
+                        bail!("{} of {} rows are invalid", invalid.len(), rows.len());
+                    }
+                    return Ok(());
+                }
+
+                require_valid_config(&config);
+                production_banner(&config);
+
+                let client = build_client(&config)?;
+                let results = run_bounded(rows, common.concurrency, |(line, email, row)| {
+                    let client = &client;
+                    async move {
+                        let outcome = match row {
+                            Ok(req) => client.create_contact(audience_id, req).await.map(|c| c.id),
+                            Err(e) => Err(e),
+                        };
+                        match outcome {
+                            Ok(id) => ImportResult {
+                                line,
+                                email,
+                                id: Some(id),
+                                error: None,
+                            },
+                            Err(e) => ImportResult {
+                                line,
+                                email,
+                                id: None,
+                                error: Some(e.to_string()),
+                            },
+                        }
+                    }
+                })
+                .await;
+
+                format_and_output(&results, &config.output_options())?;
+
+                let failed = results.iter().filter(|r| r.error.is_some()).count();
+                if failed > 0 {
+                    bail!("{} of {} contacts failed to import", failed, results.len());
+                }
+
+                Ok(())
+            }
+
+            ContactsCommands::Export {
+                audience_id,
+                file,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+
+                let client = build_client(&config)?;
+                let contacts = client.list_all_contacts(audience_id).await?;
+                let csv = contacts_csv(&contacts)?;
+
+                match file.as_deref().or(config.output.as_deref()) {
+                    Some(path) if path != STDOUT_PATH => {
+                        fs::write(path, csv)
+                            .with_context(|| format!("Failed to write CSV file: {}", path))?;
+                        eprintln!("Exported {} contacts to {}", contacts.len(), path);
+                    }
+                    _ => print!("{}", csv),
+                }
+
+                Ok(())
+            }
+
+            ContactsCommands::List {
+                audience_id,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+
+                let client = build_client(&config)?;
+                let contacts = client.list_contacts(audience_id).await?;
+
+                format_and_output(&contacts, &config.output_options())
+            }
+
+            ContactsCommands::Get {
+                id,
+                audience_id,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+
+                let client = build_client(&config)?;
+                let contact = client.get_contact(audience_id, id).await?;
+
+                format_and_output_single(&contact, &config.output_options())
+            }
+
+            ContactsCommands::Update {
+                id,
+                audience_id,
+                email,
+                first_name,
+                last_name,
+                unsubscribed,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                production_banner(&config);
+
+                let req = UpdateContactRequest {
+                    email: email.clone(),
+                    first_name: first_name.clone(),
+                    last_name: last_name.clone(),
+                    unsubscribed: *unsubscribed,
+                };
+
+                if req.is_empty() {
+                    bail!(
+                        "Nothing to update. Pass --email, --first-name, --last-name, \
+                         or --unsubscribed."
+                    );
+                }
+
+                let client = build_client(&config)?;
+                let contact = client.update_contact(audience_id, id, req).await?;
+
+                let message = format!("Contact updated successfully!\nID: {}", contact.id);
+
+                output_result(&contact, message, &config.output_options())
+            }
+
+            ContactsCommands::Delete {
+                id,
+                audience_id,
+                delete,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                confirm_production_delete(&config, &format!("contact {id}"), delete)?;
+
+                let client = build_client(&config)?;
+                client.delete_contact(audience_id, id).await?;
+
+                if common.show_messages() {
+                    println!("Contact deleted successfully!");
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Column holding each contact's email address in an import file
+const EMAIL_COLUMN: &str = "email";
+
+/// Export columns, in the order `csv_record` fills them
+const CSV_COLUMNS: [&str; 6] = [
+    "id",
+    "email",
+    "first_name",
+    "last_name",
+    "unsubscribed",
+    "created_at",
+];
+
+/// Render contacts as CSV under the `CSV_COLUMNS` header, one row each
+fn contacts_csv(contacts: &[Contact]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(CSV_COLUMNS)?;
+    for contact in contacts {
+        writer.write_record(csv_record(contact))?;
+    }
+    let bytes = writer.into_inner().context("Failed to write CSV")?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+/// A contact's values in `CSV_COLUMNS` order
+///
+/// Destructured in full so that a new `Contact` field fails to compile here
+/// until it is given a column.
+fn csv_record(contact: &Contact) -> [String; 6] {
+    let Contact {
+        id,
+        email,
+        first_name,
+        last_name,
+        unsubscribed,
+        created_at,
+    } = contact;
+    [
+        id.clone(),
+        email.clone().unwrap_or_default(),
+        first_name.clone().unwrap_or_default(),
+        last_name.clone().unwrap_or_default(),
+        unsubscribed.to_string(),
+        created_at.clone().unwrap_or_default(),
+    ]
+}
+
+/// Build a create request from an import row
+fn contact_from_row(row: &HashMap<String, String>) -> Result<CreateContactRequest> {
+    let field = |name: &str| {
+        row.get(name)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    };
+
+    let email = field(EMAIL_COLUMN).ok_or_else(|| anyhow!("Missing email"))?;
+    validate_email(email)?;
+
+    let unsubscribed = match field("unsubscribed") {
+        None => None,
+        Some(value) => Some(match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" => true,
+            "false" | "no" | "0" => false,
+            _ => bail!("Invalid unsubscribed value '{}': use true or false", value),
+        }),
+    };
+
+    Ok(CreateContactRequest {
+        email: email.to_string(),
+        first_name: field("first_name").map(str::to_string),
+        last_name: field("last_name").map(str::to_string),
+        unsubscribed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_contacts_csv_quotes_and_orders_columns() {
+        let contacts: Vec<Contact> = serde_json::from_value(json!([
+            {"id": "c1", "email": "ada@example.com", "first_name": "Ada, Countess",
+             "last_name": "Lovelace \"AL\"", "unsubscribed": false,
+             "created_at": "2025-01-15"},
+            {"id": "c2", "email": "b@example.com", "unsubscribed": true}
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            contacts_csv(&contacts).unwrap(),
+            "id,email,first_name,last_name,unsubscribed,created_at\n\
+             c1,ada@example.com,\"Ada, Countess\",\"Lovelace \"\"AL\"\"\",false,2025-01-15\n\
+             c2,b@example.com,,,true,\n"
+        );
+        assert_eq!(
+            contacts_csv(&[]).unwrap(),
+            "id,email,first_name,last_name,unsubscribed,created_at\n"
+        );
+    }
+
+    #[test]
+    fn test_contact_from_row() {
+        let req = contact_from_row(&row(&[
+            ("email", " ada@example.com "),
+            ("first_name", "Ada"),
+            ("last_name", ""),
+            ("unsubscribed", "Yes"),
+        ]))
+        .unwrap();
+        assert_eq!(req.email, "ada@example.com");
+        assert_eq!(req.first_name.as_deref(), Some("Ada"));
+        assert_eq!(req.last_name, None);
+        assert_eq!(req.unsubscribed, Some(true));
+
+        let req = contact_from_row(&row(&[("email", "b@example.com")])).unwrap();
+        assert_eq!(req.unsubscribed, None);
+    }
+
+    #[test]
+    fn test_contact_from_row_rejects_bad_rows() {
+        let err = contact_from_row(&row(&[("email", "")])).unwrap_err();
+        assert_eq!(err.to_string(), "Missing email");
+
+        let err = contact_from_row(&row(&[("email", "ada.example.com")])).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid email address"));
+
+        let err = contact_from_row(&row(&[
+            ("email", "a@example.com"),
+            ("unsubscribed", "maybe"),
+        ]))
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid unsubscribed value 'maybe': use true or false"
+        );
+    }
+}
@@ -1,13 +1,21 @@
 // ABOUTME: Domain management commands.
 // ABOUTME: Create, list, verify, update, and delete domains.
 
-use anyhow::Result;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
 use clap::Subcommand;
 
-use crate::client::ResendClient;
-use crate::commands::{build_config, require_valid_config, CommonArgs};
-use crate::formatters::{format_and_output, format_and_output_single};
-use crate::types::{CreateDomainRequest, UpdateDomainRequest};
+use crate::client::DEFAULT_TIMEOUT_SECS;
+use crate::commands::{
+    build_client, build_config, confirm_production_delete, production_banner, require_valid_config,
+    CommonArgs, DeleteArgs,
+};
+use crate::dns_check;
+use crate::formatters::{format_and_output, format_and_output_single, output_result};
+use crate::types::{
+    CreateDomainRequest, Domain, DomainStatus, Region, TlsMode, UpdateDomainRequest, REGIONS,
+};
 
 #[derive(Debug, Subcommand)]
 pub enum DomainsCommands {
@@ -16,9 +24,9 @@ pub enum DomainsCommands {
         /// Domain name
         name: String,
 
-        /// Region (us-east-1, eu-west-1, sa-east-1)
+        /// Region to send from
         #[arg(long)]
-        region: Option<String>,
+        region: Option<Region>,
 
         #[command(flatten)]
         common: CommonArgs,
@@ -26,6 +34,10 @@ pub enum DomainsCommands {
 
     /// List all domains
     List {
+        /// Only show domains with these statuses (comma-separated)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        status: Vec<DomainStatus>,
+
         #[command(flatten)]
         common: CommonArgs,
     },
@@ -35,6 +47,10 @@ pub enum DomainsCommands {
         /// Domain ID
         id: String,
 
+        /// Look up each DNS record with the system resolver and compare it with the expected value
+        #[arg(long)]
+        check_dns: bool,
+
         #[command(flatten)]
         common: CommonArgs,
     },
@@ -61,9 +77,9 @@ pub enum DomainsCommands {
         #[arg(long)]
         open_tracking: Option<bool>,
 
-        /// TLS setting (enforced, opportunistic)
+        /// TLS policy
         #[arg(long)]
-        tls: Option<String>,
+        tls: Option<TlsMode>,
 
         #[command(flatten)]
         common: CommonArgs,
@@ -74,12 +90,34 @@ pub enum DomainsCommands {
         /// Domain ID
         id: String,
 
+        #[command(flatten)]
+        delete: DeleteArgs,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// List the regions a domain can be created in
+    Regions {
         #[command(flatten)]
         common: CommonArgs,
     },
 }
 
 impl DomainsCommands {
+    /// Common arguments of the chosen subcommand
+    pub fn common_mut(&mut self) -> &mut CommonArgs {
+        match self {
+            DomainsCommands::Create { common, .. }
+            | DomainsCommands::List { common, .. }
+            | DomainsCommands::Get { common, .. }
+            | DomainsCommands::Verify { common, .. }
+            | DomainsCommands::Update { common, .. }
+            | DomainsCommands::Delete { common, .. }
+            | DomainsCommands::Regions { common, .. } => common,
+        }
+    }
+
     pub async fn execute(&self) -> Result<()> {
         match self {
             DomainsCommands::Create {
@@ -89,76 +127,86 @@ impl DomainsCommands {
             } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
+                production_banner(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
 
                 let req = CreateDomainRequest {
                     name: name.clone(),
-                    region: region.clone(),
+                    region: region.map(|r| r.name.to_string()),
                 };
 
                 let domain = client.create_domain(req).await?;
 
-                if common.json {
-                    println!("{}", serde_json::to_string_pretty(&domain)?);
-                } else {
-                    println!("Domain created successfully!");
-                    println!("ID: {}", domain.id);
-                    println!("Name: {}", domain.name);
+                let message = {
+                    let mut lines = vec![
+                        "Domain created successfully!".to_string(),
+                        format!("ID: {}", domain.id),
+                        format!("Name: {}", domain.name),
+                    ];
                     if let Some(records) = &domain.records {
-                        println!();
-                        println!("DNS Records to add:");
+                        lines.push(String::new());
+                        lines.push("DNS Records to add:".to_string());
                         for record in records {
-                            println!(
+                            lines.push(format!(
                                 "  {} {} -> {}",
                                 record.record, record.name, record.value
-                            );
+                            ));
                         }
                     }
-                }
+                    lines.join("\n")
+                };
 
-                Ok(())
+                output_result(&domain, message, &config.output_options())
             }
 
-            DomainsCommands::List { common } => {
+            DomainsCommands::List { status, common } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
-                let domains = client.list_domains().await?;
+                let client = build_client(&config)?;
+                let domains = filter_by_status(client.list_domains().await?, status);
 
-                format_and_output(&domains, config.format, config.output.as_deref())
+                format_and_output(&domains, &config.output_options())
             }
 
-            DomainsCommands::Get { id, common } => {
+            DomainsCommands::Get {
+                id,
+                check_dns,
+                common,
+            } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
                 let domain = client.get_domain(id).await?;
 
-                format_and_output_single(&domain, config.format, config.output.as_deref())
+                if *check_dns {
+                    let timeout =
+                        Duration::from_secs(config.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS));
+                    let resolver = dns_check::system_resolver(timeout);
+                    let checks = dns_check::check_records(&domain, resolver.as_ref()).await;
+                    return format_and_output(&checks, &config.output_options());
+                }
+
+                format_and_output_single(&domain, &config.output_options())
             }
 
             DomainsCommands::Verify { id, common } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
+                production_banner(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
                 let domain = client.verify_domain(id).await?;
 
-                if common.json {
-                    println!("{}", serde_json::to_string_pretty(&domain)?);
-                } else {
-                    println!("Verification initiated!");
-                    println!("ID: {}", domain.id);
-                    println!(
-                        "Status: {}",
-                        domain.status.as_deref().unwrap_or("pending")
-                    );
-                }
+                let message = format!(
+                    "Verification initiated!\nID: {}\nStatus: {}",
+                    domain.id,
+                    domain.status.as_deref().unwrap_or("pending")
+                );
 
-                Ok(())
+                output_result(&domain, message, &config.output_options())
             }
 
             DomainsCommands::Update {
@@ -170,40 +218,127 @@ impl DomainsCommands {
             } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
-
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                production_banner(&config);
 
                 let req = UpdateDomainRequest {
                     click_tracking: *click_tracking,
                     open_tracking: *open_tracking,
-                    tls: tls.clone(),
+                    tls: *tls,
                 };
 
+                if req.is_empty() {
+                    bail!("Nothing to update. Pass --click-tracking, --open-tracking, or --tls.");
+                }
+
+                let client = build_client(&config)?;
+
                 let domain = client.update_domain(id, req).await?;
 
-                if common.json {
-                    println!("{}", serde_json::to_string_pretty(&domain)?);
-                } else {
-                    println!("Domain updated successfully!");
-                    println!("ID: {}", domain.id);
-                }
+                let message = format!("Domain updated successfully!\nID: {}", domain.id);
 
-                Ok(())
+                output_result(&domain, message, &config.output_options())
             }
 
-            DomainsCommands::Delete { id, common } => {
+            DomainsCommands::Delete { id, delete, common } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
+                confirm_production_delete(&config, &format!("domain {id}"), delete)?;
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
                 client.delete_domain(id).await?;
 
-                if !common.json {
+                if common.show_messages() {
                     println!("Domain deleted successfully!");
                 }
 
                 Ok(())
             }
+
+            DomainsCommands::Regions { common } => {
+                let config = build_config(common)?;
+                format_and_output(REGIONS, &config.output_options())
+            }
+        }
+    }
+}
+
+/// Keep only domains whose status matches one of the given statuses
+fn filter_by_status(domains: Vec<Domain>, statuses: &[DomainStatus]) -> Vec<Domain> {
+    if statuses.is_empty() {
+        return domains;
+    }
+
+    domains
+        .into_iter()
+        .filter(|d| {
+            d.status
+                .as_deref()
+                .is_some_and(|s| statuses.iter().any(|st| st.as_str() == s))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn domain(id: &str, status: Option<&str>) -> Domain {
+        Domain {
+            id: id.to_string(),
+            name: format!("{id}.example.com"),
+            status: status.map(|s| s.to_string()),
+            region: None,
+            records: None,
+        }
+    }
+
+    #[test]
+    fn test_filter_by_status_empty_keeps_all() {
+        let domains = vec![domain("a", Some("verified")), domain("b", None)];
+        assert_eq!(filter_by_status(domains, &[]).len(), 2);
+    }
+
+    #[test]
+    fn test_filter_by_status_multiple() {
+        let domains = vec![
+            domain("a", Some("verified")),
+            domain("b", Some("pending")),
+            domain("c", Some("failed")),
+            domain("d", None),
+        ];
+        let filtered = filter_by_status(domains, &[DomainStatus::Verified, DomainStatus::Pending]);
+        let ids: Vec<&str> = filtered.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_update_request_empty_and_serialization() {
+        let empty = UpdateDomainRequest {
+            click_tracking: None,
+            open_tracking: None,
+            tls: None,
+        };
+        assert!(empty.is_empty());
+
+        let req = UpdateDomainRequest {
+            click_tracking: None,
+            open_tracking: Some(true),
+            tls: Some(TlsMode::Enforced),
+        };
+        assert!(!req.is_empty());
+        assert_eq!(
+            serde_json::to_value(&req).unwrap(),
+            serde_json::json!({"open_tracking": true, "tls": "enforced"})
+        );
+    }
+
+    #[test]
+    fn test_region_parses_every_listed_region() {
+        use clap::ValueEnum;
+
+        for region in REGIONS {
+            assert_eq!(Region::from_str(region.name, false).unwrap(), *region);
         }
+        assert!(Region::from_str("mars-north-1", false).is_err());
     }
 }
@@ -1,38 +1,127 @@
 // ABOUTME: Email management commands.
 // ABOUTME: Send, list, get, cancel, and update emails.
 
-use anyhow::Result;
-use clap::Subcommand;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Local, Utc};
+use clap::{Subcommand, ValueEnum};
+use dialoguer::Confirm;
+use directories::ProjectDirs;
+use pulldown_cmark::{Options, Parser};
+use reqwest::Url;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::process::Command;
 
-use crate::client::ResendClient;
-use crate::commands::{build_config, require_valid_config, CommonArgs};
-use crate::formatters::{format_and_output, format_and_output_single};
-use crate::types::{SendEmailRequest, UpdateEmailRequest};
+use crate::checkpoint::Checkpoint;
+use crate::client::{new_idempotency_key, ResendClient, MAX_BATCH_SIZE};
+use crate::commands::{
+    build_client, build_config, production_banner, require_valid_config, CommonArgs,
+};
+use crate::concurrency::run_bounded;
+use crate::dates::{parse_timestamp, resolve_schedule};
+use crate::dedup::{request_hash, DedupStore};
+use crate::formatters::{
+    format_and_output, format_and_output_grouped, format_and_output_single, output_result,
+};
+use crate::temp_files::{remove_stale, write_kept_in};
+use crate::templating::render;
+use crate::types::{
+    Attachment, Email, EmailStatus, MergeResult, Priority, RecipientResult, SendEmailRequest, Tag,
+    Template, UpdateEmailRequest,
+};
+use crate::validation::validate_email;
+
+/// Recipient count above which `emails send` asks before sending
+const DEFAULT_CONFIRM_THRESHOLD: usize = 50;
 
 #[derive(Debug, Subcommand)]
+#[allow(clippy::large_enum_variant)]
 pub enum EmailsCommands {
     /// Send an email
     Send {
-        /// Sender email address
-        #[arg(long)]
-        from: String,
+        /// Sender, as an address or "Name <address>"
+        #[arg(
+            long,
+            required_unless_present = "from_email",
+            conflicts_with_all = ["from_name", "from_email"]
+        )]
+        from: Option<String>,
+
+        /// Sender display name, combined with --from-email
+        #[arg(long, value_name = "NAME", requires = "from_email")]
+        from_name: Option<String>,
+
+        /// Sender email address, used with --from-name instead of --from
+        #[arg(long, value_name = "ADDRESS")]
+        from_email: Option<String>,
 
         /// Recipient email address(es)
-        #[arg(long, required = true)]
+        #[arg(long, required_unless_present = "to_file")]
         to: Vec<String>,
 
+        /// Read recipients from a file, one per line; blank lines and `#`
+        /// comments are ignored
+        #[arg(long, value_name = "PATH")]
+        to_file: Option<String>,
+
         /// Email subject
-        #[arg(long)]
-        subject: String,
+        #[arg(long, required_unless_present = "template_id")]
+        subject: Option<String>,
+
+        /// Send a stored template; subject and body come from it
+        #[arg(
+            long,
+            value_name = "ID",
+            conflicts_with_all = ["subject", "html", "html_file", "text", "text_file"]
+        )]
+        template_id: Option<String>,
+
+        /// Template variable: KEY=VALUE (repeatable)
+        #[arg(
+            long,
+            value_name = "KEY=VALUE",
+            value_parser = parse_var,
+            requires = "template_id",
+            conflicts_with = "subject"
+        )]
+        var: Vec<(String, String)>,
 
         /// HTML content
         #[arg(long)]
         html: Option<String>,
 
+        /// Read the HTML content from a file
+        #[arg(long, value_name = "PATH", conflicts_with = "html")]
+        html_file: Option<String>,
+
         /// Plain text content
         #[arg(long)]
         text: Option<String>,
 
+        /// Read the plain text content from a file, or stdin with `-`
+        #[arg(long, value_name = "PATH", conflicts_with = "text")]
+        text_file: Option<String>,
+
+        /// Markdown content; sent as HTML, with the Markdown as the plain text part
+        #[arg(
+            long,
+            conflicts_with_all = ["html", "html_file", "text", "text_file", "template_id"]
+        )]
+        markdown: Option<String>,
+
+        /// Read the Markdown content from a file, or stdin with `-`
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with_all = ["markdown", "html", "html_file", "text", "text_file", "template_id"]
+        )]
+        markdown_file: Option<String>,
+
         /// CC recipients
         #[arg(long)]
         cc: Option<Vec<String>>,
@@ -45,10 +134,182 @@ pub enum EmailsCommands {
         #[arg(long)]
         reply_to: Option<Vec<String>>,
 
-        /// Schedule send time (ISO 8601)
-        #[arg(long)]
+        /// Schedule send time: ISO 8601, or e.g. "in 2 hours", "+30m", "tomorrow 9am"
+        #[arg(long, value_name = "WHEN", value_parser = parse_scheduled_at)]
         scheduled_at: Option<String>,
 
+        /// Redirect all recipients to this address (overrides the profile's redirect_to)
+        #[arg(long, value_name = "ADDRESS")]
+        redirect_to: Option<String>,
+
+        /// JSON file of custom header names to values
+        #[arg(long, value_name = "PATH")]
+        headers_file: Option<String>,
+
+        /// Add a custom header, e.g. "X-Entity-Ref-ID: 123" (repeatable);
+        /// replaces a --headers-file header of the same name in any case
+        #[arg(long, value_name = "NAME: VALUE", value_parser = parse_header)]
+        header: Vec<(String, String)>,
+
+        /// Attach a local file (repeatable)
+        #[arg(long, value_name = "PATH")]
+        attach: Vec<String>,
+
+        /// Attach a hosted file by URL, optionally named: [NAME=]URL (repeatable)
+        #[arg(long, value_name = "[NAME=]URL", value_parser = parse_attach_url)]
+        attach_url: Vec<Attachment>,
+
+        /// Embed an image for the HTML to show with `<img src="cid:CID">`;
+        /// CID defaults to the file name: [CID=]PATH (repeatable)
+        #[arg(long, value_name = "[CID=]PATH", value_parser = parse_inline)]
+        inline: Vec<(String, String)>,
+
+        /// Tag the email for analytics: NAME=VALUE (repeatable)
+        #[arg(long, value_name = "NAME=VALUE", value_parser = parse_tag)]
+        tag: Vec<Tag>,
+
+        /// Mark the email's importance with X-Priority/Importance headers;
+        /// either header given with --header or --headers-file wins
+        #[arg(long, value_enum)]
+        priority: Option<Priority>,
+
+        /// Ask for confirmation above this many recipients (to + cc + bcc)
+        #[arg(long, value_name = "COUNT", default_value_t = DEFAULT_CONFIRM_THRESHOLD)]
+        confirm_threshold: usize,
+
+        /// Refuse to repeat an identical send made within this many seconds
+        #[arg(long, value_name = "SECONDS")]
+        dedup_window: Option<u64>,
+
+        /// Send even if an identical email was sent within the dedup window
+        #[arg(long, requires = "dedup_window")]
+        force: bool,
+
+        /// Skip the large-recipient confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Send one email per --to address so recipients don't see each other
+        #[arg(long)]
+        separate: bool,
+
+        /// Let the API drop repeats of this send; with --separate, each
+        /// recipient gets KEY-ADDRESS
+        #[arg(long, value_name = "KEY")]
+        idempotency_key: Option<String>,
+
+        /// Generate a fresh idempotency key for this send so retries of it
+        /// can't deliver twice
+        #[arg(long, conflicts_with = "idempotency_key")]
+        auto_idempotency: bool,
+
+        /// Print the JSON payload that would be sent instead of sending it
+        #[arg(long)]
+        dry_run: bool,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Send up to 100 emails in one request from a JSON file
+    SendBatch {
+        /// JSON file holding an array of emails, each shaped like a single send
+        #[arg(long, value_name = "PATH")]
+        file: String,
+
+        /// Skip the first N emails, e.g. those sent before an interruption
+        #[arg(long, value_name = "N", conflicts_with = "checkpoint")]
+        resume_from: Option<usize>,
+
+        /// Record progress in this file and skip what it says was already sent
+        #[arg(long, value_name = "PATH")]
+        checkpoint: Option<String>,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Send a local template to each row of a CSV, in batches of 100
+    SendCsv {
+        /// Sender email address
+        #[arg(long)]
+        from: String,
+
+        /// CSV file with a `to` column, an optional `subject` column, and one
+        /// column per template variable
+        #[arg(long, value_name = "PATH")]
+        file: String,
+
+        /// Body template with {{variables}}; HTML, or plain text for .txt files
+        #[arg(long, value_name = "PATH")]
+        template_file: String,
+
+        /// Subject for rows without a `subject` column value; may use {{variables}}
+        #[arg(long)]
+        subject: Option<String>,
+
+        /// Skip the first N rows, e.g. those sent before an interruption
+        #[arg(long, value_name = "N", conflicts_with = "checkpoint")]
+        resume_from: Option<usize>,
+
+        /// Record progress in this file and skip the rows it says were
+        /// already sent; sending stops at the first failed batch
+        #[arg(long, value_name = "PATH")]
+        checkpoint: Option<String>,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Send a stored template to each row of a CSV (mail merge)
+    SendMerge {
+        /// Sender email address
+        #[arg(long)]
+        from: String,
+
+        /// CSV file with an `email` column plus one column per template variable
+        #[arg(long, value_name = "PATH")]
+        csv: String,
+
+        /// Template ID to render for each row
+        #[arg(long)]
+        template: String,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Send a copy of an existing email as a new message
+    Resend {
+        /// ID of the email to copy
+        id: String,
+
+        /// HTML content; with --html or --text the stored body is not reused
+        #[arg(long)]
+        html: Option<String>,
+
+        /// Plain text content; with --html or --text the stored body is not reused
+        #[arg(long)]
+        text: Option<String>,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Open an email's HTML in the browser to check how it renders
+    Preview {
+        /// Preview a sent email's stored HTML
+        #[arg(required_unless_present = "html_file", conflicts_with = "html_file")]
+        id: Option<String>,
+
+        /// Preview a local HTML file, or stdin with `-`
+        #[arg(long, value_name = "PATH")]
+        html_file: Option<String>,
+
+        /// Only write the preview file and print its path
+        #[arg(long)]
+        no_open: bool,
+
         #[command(flatten)]
         common: CommonArgs,
     },
@@ -58,12 +319,59 @@ pub enum EmailsCommands {
         /// Email ID
         id: String,
 
+        /// Show the email's delivery events with timestamps instead
+        #[arg(long)]
+        events: bool,
+
         #[command(flatten)]
         common: CommonArgs,
     },
 
     /// List emails
     List {
+        /// Show emails in one sub-table per value of this column (e.g. status)
+        #[arg(long, value_name = "COLUMN")]
+        group_by: Option<String>,
+
+        /// Emails per page (1-100)
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..=100))]
+        limit: Option<u32>,
+
+        /// Start after this email ID, as printed at the end of the previous page
+        #[arg(long, value_name = "CURSOR")]
+        after: Option<String>,
+
+        /// Fetch every page instead of just the first
+        #[arg(long, conflicts_with = "after")]
+        all: bool,
+
+        /// Only show emails whose latest event is one of these (comma-separated)
+        #[arg(long, value_enum, value_delimiter = ',', ignore_case = true)]
+        status: Vec<EmailStatus>,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// List emails scheduled to send in the future, from every page of emails
+    Scheduled {
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Wait until an email is delivered, bounces, or is marked as spam
+    Wait {
+        /// Email ID
+        id: String,
+
+        /// Seconds between status checks
+        #[arg(long, value_name = "SECONDS", default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+
+        /// Give up after this many seconds
+        #[arg(long, value_name = "SECONDS", default_value_t = 300)]
+        wait_timeout: u64,
+
         #[command(flatten)]
         common: CommonArgs,
     },
@@ -73,6 +381,10 @@ pub enum EmailsCommands {
         /// Email ID
         id: String,
 
+        /// Fetch the email first and refuse if it is no longer scheduled
+        #[arg(long)]
+        check: bool,
+
         #[command(flatten)]
         common: CommonArgs,
     },
@@ -82,8 +394,12 @@ pub enum EmailsCommands {
         /// Email ID
         id: String,
 
-        /// New scheduled time (ISO 8601)
+        /// Fetch the email first and refuse if it is no longer scheduled
         #[arg(long)]
+        check: bool,
+
+        /// New scheduled time: ISO 8601, or e.g. "in 2 hours", "+30m", "tomorrow 9am"
+        #[arg(long, value_name = "WHEN", value_parser = parse_scheduled_at)]
         scheduled_at: String,
 
         #[command(flatten)]
@@ -92,95 +408,640 @@ pub enum EmailsCommands {
 }
 
 impl EmailsCommands {
+    /// Common arguments of the chosen subcommand
+    pub fn common_mut(&mut self) -> &mut CommonArgs {
+        match self {
+            EmailsCommands::Send { common, .. }
+            | EmailsCommands::SendBatch { common, .. }
+            | EmailsCommands::SendCsv { common, .. }
+            | EmailsCommands::SendMerge { common, .. }
+            | EmailsCommands::Resend { common, .. }
+            | EmailsCommands::Preview { common, .. }
+            | EmailsCommands::Get { common, .. }
+            | EmailsCommands::List { common, .. }
+            | EmailsCommands::Scheduled { common, .. }
+            | EmailsCommands::Wait { common, .. }
+            | EmailsCommands::Cancel { common, .. }
+            | EmailsCommands::Update { common, .. } => common,
+        }
+    }
+
     pub async fn execute(&self) -> Result<()> {
         match self {
             EmailsCommands::Send {
                 from,
+                from_name,
+                from_email,
                 to,
+                to_file,
                 subject,
+                template_id,
+                var,
                 html,
+                html_file,
                 text,
+                text_file,
+                markdown,
+                markdown_file,
                 cc,
                 bcc,
                 reply_to,
                 scheduled_at,
+                redirect_to,
+                headers_file,
+                header,
+                attach,
+                attach_url,
+                inline,
+                tag,
+                priority,
+                dedup_window,
+                force,
+                confirm_threshold,
+                yes,
+                separate,
+                idempotency_key,
+                auto_idempotency,
+                dry_run,
                 common,
             } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
+                production_banner(&config);
+
+                if html_file.as_deref() == Some(STDIN_PATH)
+                    && text_file.as_deref() == Some(STDIN_PATH)
+                {
+                    bail!("Only one of --html-file and --text-file can read from stdin");
+                }
+
+                let mut headers = match headers_file {
+                    Some(path) => Some(load_headers_file(Path::new(path))?),
+                    None => None,
+                };
+                if !header.is_empty() {
+                    let headers = headers.get_or_insert_with(HashMap::new);
+                    for (name, value) in header {
+                        set_header(headers, name, value);
+                    }
+                }
+                if let Some(priority) = priority {
+                    let headers = headers.get_or_insert_with(HashMap::new);
+                    for (name, value) in priority.headers() {
+                        set_header_default(headers, name, value);
+                    }
+                }
+
+                let to = match to_file {
+                    Some(path) => merge_recipients(to, read_recipients_file(Path::new(path))?),
+                    None => merge_recipients(to, Vec::new()),
+                };
+                if to.is_empty() {
+                    bail!("No recipients: the --to-file list is empty");
+                }
+
+                for address in to
+                    .iter()
+                    .chain(cc.iter().flatten())
+                    .chain(bcc.iter().flatten())
+                    .chain(reply_to.iter().flatten())
+                {
+                    validate_email(address)?;
+                }
+
+                let mut attachments = load_attachments(attach, inline)?;
+                attachments.extend(attach_url.iter().cloned());
+
+                let client = build_client(&config)?;
+
+                let (subject, html, text) = match template_id {
+                    Some(id) => {
+                        let template = client.get_template(id).await?;
+                        render_template(&template, &var.iter().cloned().collect())?
+                    }
+                    None => {
+                        let subject = subject.clone().unwrap_or_default();
+                        match resolve_body(markdown, markdown_file.as_deref(), "Markdown")? {
+                            Some(source) => {
+                                (subject, Some(markdown_to_html(&source)), Some(source))
+                            }
+                            None => (
+                                subject,
+                                resolve_body(html, html_file.as_deref(), "HTML")?,
+                                resolve_body(text, text_file.as_deref(), "text")?,
+                            ),
+                        }
+                    }
+                };
+
+                let idempotency_key = match idempotency_key {
+                    Some(key) => Some(key.clone()),
+                    None if *auto_idempotency => {
+                        let key = new_idempotency_key()?;
+                        if config.verbose {
+                            eprintln!("Idempotency-Key: {}", key);
+                        }
+                        Some(key)
+                    }
+                    None => None,
+                };
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let from = match (from, from_email) {
+                    (Some(from), _) => from.clone(),
+                    (None, Some(email)) => format_mailbox(from_name.as_deref(), email),
+                    (None, None) => bail!("Pass --from or --from-email"),
+                };
 
-                let req = SendEmailRequest {
-                    from: from.clone(),
-                    to: to.clone(),
-                    subject: subject.clone(),
-                    html: html.clone(),
-                    text: text.clone(),
+                let mut req = SendEmailRequest {
+                    from,
+                    to,
+                    subject,
+                    html,
+                    text,
                     cc: cc.clone(),
                     bcc: bcc.clone(),
                     reply_to: reply_to.clone(),
                     scheduled_at: scheduled_at.clone(),
+                    headers,
+                    attachments: (!attachments.is_empty()).then_some(attachments),
+                    tags: (!tag.is_empty()).then(|| tag.clone()),
                 };
 
-                let response = client.send_email(req).await?;
+                if let Some(address) = redirect_to.as_ref().or(config.redirect_to.as_ref()) {
+                    eprintln!(
+                        "*** REDIRECT ACTIVE: sending to {} instead of {} recipient(s) ***",
+                        address,
+                        req.recipient_count()
+                    );
+                    apply_redirect(&mut req, address);
+                }
 
-                if common.json {
-                    println!("{}", serde_json::to_string_pretty(&response)?);
-                } else {
-                    println!("Email sent successfully!");
-                    println!("ID: {}", response.id);
+                if *dry_run {
+                    let options = config.output_options();
+                    if *separate && req.to.len() > 1 {
+                        let requests = split_by_recipient(&req);
+                        let key = idempotency_key
+                            .as_ref()
+                            .map(|key| format!("{}-ADDRESS", key));
+                        let message = dry_run_message(&requests, key.as_deref())?;
+                        return output_result(&requests, message, &options);
+                    }
+                    let message = dry_run_message(&req, idempotency_key.as_deref())?;
+                    return output_result(&req, message, &options);
+                }
+
+                confirm_recipients(req.recipient_count(), *confirm_threshold, *yes)?;
+
+                let now = Utc::now().timestamp();
+                let dedup = match dedup_window {
+                    Some(window) => {
+                        let path = DedupStore::default_path()
+                            .ok_or_else(|| anyhow!("Could not determine cache directory"))?;
+                        Some((*window, request_hash(&req)?, DedupStore::load(&path)))
+                    }
+                    None => None,
+                };
+
+                if let Some((window, hash, store)) = &dedup {
+                    if !force && store.is_duplicate(hash, *window, now) {
+                        bail!(
+                            "Duplicate send suppressed: an identical email was sent in the last \
+                             {}s. Use --force to send anyway.",
+                            window
+                        );
+                    }
+                }
+
+                if *separate && req.to.len() > 1 {
+                    let requests = split_by_recipient(&req);
+                    let results = run_bounded(requests, common.concurrency, |req| {
+                        let client = &client;
+                        let key = idempotency_key
+                            .as_ref()
+                            .map(|key| format!("{}-{}", key, req.to[0]));
+                        async move {
+                            let to = req.to[0].clone();
+                            match client.send_email(req, key.as_deref()).await {
+                                Ok(response) => RecipientResult {
+                                    to,
+                                    id: Some(response.id),
+                                    error: None,
+                                },
+                                Err(e) => RecipientResult {
+                                    to,
+                                    id: None,
+                                    error: Some(e.to_string()),
+                                },
+                            }
+                        }
+                    })
+                    .await;
+
+                    if let Some((window, hash, mut store)) = dedup {
+                        store.record(&hash, window, now);
+                        store.save()?;
+                    }
+
+                    format_and_output(&results, &config.output_options())?;
+
+                    let failed = results.iter().filter(|r| r.error.is_some()).count();
+                    if failed > 0 {
+                        bail!("{} of {} sends failed", failed, results.len());
+                    }
+                    return Ok(());
+                }
+
+                let response = client.send_email(req, idempotency_key.as_deref()).await?;
+
+                if let Some((window, hash, mut store)) = dedup {
+                    store.record(&hash, window, now);
+                    store.save()?;
+                }
+
+                let message = format!("Email sent successfully!\nID: {}", response.id);
+
+                output_result(&response, message, &config.output_options())
+            }
+
+            EmailsCommands::SendBatch {
+                file,
+                resume_from,
+                checkpoint,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                production_banner(&config);
+
+                let mut reqs = load_batch_file(Path::new(file))?;
+                let total = reqs.len();
+                let mut checkpoint = checkpoint
+                    .as_deref()
+                    .map(|path| Checkpoint::load(Path::new(path), Path::new(file)))
+                    .transpose()?;
+                let start = resume_start(*resume_from, checkpoint.as_ref(), total)?;
+                if start == total {
+                    eprintln!("All {} emails were already sent", total);
+                    return Ok(());
+                }
+                reqs.drain(..start);
+                if let Some(address) = &config.redirect_to {
+                    eprintln!(
+                        "*** REDIRECT ACTIVE: sending {} email(s) to {} instead ***",
+                        reqs.len(),
+                        address
+                    );
+                    for req in &mut reqs {
+                        apply_redirect(req, address);
+                    }
+                }
+
+                let client = build_client(&config)?;
+                let sent = client.send_email_batch(reqs).await?;
+                if let Some(checkpoint) = &mut checkpoint {
+                    checkpoint.record(total)?;
+                }
+
+                format_and_output(&sent, &config.output_options())
+            }
+
+            EmailsCommands::Resend {
+                id,
+                html,
+                text,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                production_banner(&config);
+
+                let client = build_client(&config)?;
+                let original = client.get_email(id).await?;
+                let mut req = resend_request(original, html, text)?;
+
+                if let Some(address) = &config.redirect_to {
+                    eprintln!(
+                        "*** REDIRECT ACTIVE: sending to {} instead of {} recipient(s) ***",
+                        address,
+                        req.recipient_count()
+                    );
+                    apply_redirect(&mut req, address);
+                }
+
+                let response = client.send_email(req, None).await?;
+                let message = format!("Email resent successfully!\nID: {}", response.id);
+
+                output_result(&response, message, &config.output_options())
+            }
+
+            EmailsCommands::SendCsv {
+                from,
+                file,
+                template_file,
+                subject,
+                resume_from,
+                checkpoint,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                production_banner(&config);
+
+                let body = fs::read_to_string(template_file)
+                    .with_context(|| format!("Failed to read template file: {}", template_file))?;
+                let is_text = Path::new(template_file)
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("txt"));
+
+                if let Some(address) = &config.redirect_to {
+                    eprintln!(
+                        "*** REDIRECT ACTIVE: sending every row to {} instead ***",
+                        address
+                    );
+                }
+
+                let rows = read_csv_rows(Path::new(file), CSV_TO_COLUMN)?;
+                let total = rows.len();
+                let mut checkpoint = checkpoint
+                    .as_deref()
+                    .map(|path| Checkpoint::load(Path::new(path), Path::new(file)))
+                    .transpose()?;
+                let start = resume_start(*resume_from, checkpoint.as_ref(), total)?;
+
+                let mut results = Vec::new();
+                let mut pending = Vec::new();
+                for (index, (line, row)) in rows.into_iter().enumerate().skip(start) {
+                    let to = row
+                        .as_ref()
+                        .ok()
+                        .and_then(|vars| vars.get(CSV_TO_COLUMN))
+                        .cloned()
+                        .unwrap_or_default();
+                    let outcome = row.and_then(|vars| {
+                        let template = Template {
+                            id: String::new(),
+                            name: String::new(),
+                            subject: vars
+                                .get("subject")
+                                .filter(|s| !s.trim().is_empty())
+                                .or(subject.as_ref())
+                                .cloned(),
+                            html: (!is_text).then(|| body.clone()),
+                            text: is_text.then(|| body.clone()),
+                            created_at: None,
+                        };
+                        if template.subject.is_none() {
+                            bail!("Row has no subject; add a 'subject' column or pass --subject");
+                        }
+                        let mut req = merge_request(from, &template, &vars, CSV_TO_COLUMN)?;
+                        if let Some(address) = &config.redirect_to {
+                            apply_redirect(&mut req, address);
+                        }
+                        Ok(req)
+                    });
+                    match outcome {
+                        Ok(req) => pending.push((index, line, to, req)),
+                        Err(e) => results.push(MergeResult {
+                            line,
+                            to,
+                            id: None,
+                            error: Some(e.to_string()),
+                        }),
+                    }
+                }
+
+                let client = build_client(&config)?;
+                let sent = send_in_batches(&client, &pending, checkpoint.as_mut(), total).await?;
+                results.extend(sent);
+                results.sort_by_key(|r| r.line);
+
+                format_and_output(&results, &config.output_options())?;
+
+                let failed = results.iter().filter(|r| r.error.is_some()).count();
+                if failed > 0 {
+                    bail!("{} of {} rows failed", failed, results.len());
+                }
+
+                Ok(())
+            }
+
+            EmailsCommands::SendMerge {
+                from,
+                csv,
+                template,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                production_banner(&config);
+
+                let rows = read_merge_rows(Path::new(csv))?;
+                let client = build_client(&config)?;
+                let template = client.get_template(template).await?;
+
+                let redirect_to = config.redirect_to.as_deref();
+                if let Some(address) = redirect_to {
+                    eprintln!(
+                        "*** REDIRECT ACTIVE: sending every row to {} instead ***",
+                        address
+                    );
+                }
+
+                let results = run_bounded(rows, common.concurrency, |(line, vars)| {
+                    send_merge_row(&client, from, &template, line, vars, redirect_to)
+                })
+                .await;
+
+                format_and_output(&results, &config.output_options())?;
+
+                let failed = results.iter().filter(|r| r.error.is_some()).count();
+                if failed > 0 {
+                    bail!("{} of {} sends failed", failed, results.len());
+                }
+
+                Ok(())
+            }
+
+            EmailsCommands::Preview {
+                id,
+                html_file,
+                no_open,
+                common,
+            } => {
+                let html = match id {
+                    Some(id) => {
+                        let config = build_config(common)?;
+                        require_valid_config(&config);
+
+                        let client = build_client(&config)?;
+                        client
+                            .get_email(id)
+                            .await?
+                            .html
+                            .ok_or_else(|| anyhow!("Email {} has no HTML body to preview", id))?
+                    }
+                    None => resolve_body(&None, html_file.as_deref(), "HTML")?.unwrap_or_default(),
+                };
+
+                let dir = preview_dir()?;
+                if *no_open {
+                    let path = write_preview(&dir, &html)?;
+                    println!("{}", path.display());
+                    return Ok(());
                 }
 
+                open_preview(&dir, &html, |path| async move {
+                    eprintln!("Opening {}", path.display());
+                    open_in_browser(&path).await
+                })
+                .await?;
                 Ok(())
             }
 
-            EmailsCommands::Get { id, common } => {
+            EmailsCommands::Get { id, events, common } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
                 let email = client.get_email(id).await?;
 
-                format_and_output_single(&email, config.format, config.output.as_deref())
+                if *events {
+                    let history = email.events.unwrap_or_default();
+                    if history.is_empty() {
+                        if let Some(last) = &email.last_event {
+                            eprintln!(
+                                "The API returned no event history for {}; latest event: {}",
+                                id, last
+                            );
+                        }
+                    }
+                    return format_and_output(&history, &config.output_options());
+                }
+
+                format_and_output_single(&email, &config.output_options())
+            }
+
+            EmailsCommands::List {
+                group_by,
+                limit,
+                after,
+                all,
+                status,
+                common,
+            } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+
+                let client = build_client(&config)?;
+                let emails = if *all {
+                    client.list_all_emails(*limit).await?
+                } else {
+                    let page = client.list_emails_page(*limit, after.as_deref()).await?;
+                    if let Some(cursor) = page.next_cursor() {
+                        eprintln!("More emails available; continue with --after {}", cursor);
+                    }
+                    page.data
+                };
+                let emails = filter_by_status(emails, status);
+
+                match group_by {
+                    Some(column) => {
+                        format_and_output_grouped(&emails, column, &config.output_options())
+                    }
+                    None => format_and_output(&emails, &config.output_options()),
+                }
             }
 
-            EmailsCommands::List { common } => {
+            EmailsCommands::Scheduled { common } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
-                let emails = client.list_emails().await?;
+                let client = build_client(&config)?;
+                // Scheduled emails can sit on any page, so read them all
+                let emails = scheduled_emails(client.list_all_emails(Some(100)).await?, Utc::now());
 
-                format_and_output(&emails, config.format, config.output.as_deref())
+                format_and_output(&emails, &config.output_options())
             }
 
-            EmailsCommands::Cancel { id, common } => {
+            EmailsCommands::Wait {
+                id,
+                interval,
+                wait_timeout,
+                common,
+            } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
-                let email = client.cancel_email(id).await?;
+                let client = build_client(&config)?;
+                let interval = Duration::from_secs(*interval);
+                let deadline = Instant::now() + Duration::from_secs(*wait_timeout);
+                let mut last_event: Option<String> = None;
 
-                if common.json {
-                    println!("{}", serde_json::to_string_pretty(&email)?);
-                } else {
-                    println!("Email cancelled successfully!");
-                    println!("ID: {}", email.id);
+                loop {
+                    let email = client.get_email(id).await?;
+                    let event = email.last_event.clone().unwrap_or_default();
+                    if config.verbose && last_event.as_deref() != Some(event.as_str()) {
+                        eprintln!(
+                            "{}: {}",
+                            id,
+                            if event.is_empty() { "pending" } else { &event }
+                        );
+                    }
+
+                    if let Some(delivered) = delivery_outcome(&event) {
+                        let message = format!("Email {} {}", id, event);
+                        output_result(&email, message, &config.output_options())?;
+                        if !delivered {
+                            bail!("Email {} was not delivered: {}", id, event);
+                        }
+                        return Ok(());
+                    }
+
+                    if Instant::now() + interval > deadline {
+                        bail!(
+                            "Email {} still {} after {}s",
+                            id,
+                            if event.is_empty() { "pending" } else { &event },
+                            wait_timeout
+                        );
+                    }
+                    last_event = Some(event);
+                    tokio::time::sleep(interval).await;
                 }
+            }
 
-                Ok(())
+            EmailsCommands::Cancel { id, check, common } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+                production_banner(&config);
+
+                let client = build_client(&config)?;
+                if *check {
+                    let email = client.get_email(id).await?;
+                    ensure_scheduled(&email, Utc::now(), "cancel")?;
+                }
+                let email = client.cancel_email(id).await?;
+
+                let message = format!("Email cancelled successfully!\nID: {}", email.id);
+
+                output_result(&email, message, &config.output_options())
             }
 
             EmailsCommands::Update {
                 id,
+                check,
                 scheduled_at,
                 common,
             } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
+                production_banner(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
+                if *check {
+                    let email = client.get_email(id).await?;
+                    ensure_scheduled(&email, Utc::now(), "update")?;
+                }
 
                 let req = UpdateEmailRequest {
                     scheduled_at: scheduled_at.clone(),
@@ -188,15 +1049,1507 @@ impl EmailsCommands {
 
                 let email = client.update_email(id, req).await?;
 
-                if common.json {
-                    println!("{}", serde_json::to_string_pretty(&email)?);
-                } else {
-                    println!("Email updated successfully!");
-                    println!("ID: {}", email.id);
-                }
+                let message = format!("Email updated successfully!\nID: {}", email.id);
 
-                Ok(())
+                output_result(&email, message, &config.output_options())
             }
         }
     }
 }
+
+/// Path that means "read from stdin" for body files
+const STDIN_PATH: &str = "-";
+
+/// The body given inline, or read from `file` when that is set instead
+///
+/// A file path of `-` reads stdin. An empty file is an error rather than an
+/// email with an empty body.
+fn resolve_body(inline: &Option<String>, file: Option<&str>, kind: &str) -> Result<Option<String>> {
+    let Some(path) = file else {
+        return Ok(inline.clone());
+    };
+    let contents = if path == STDIN_PATH {
+        std::io::read_to_string(std::io::stdin())
+            .with_context(|| format!("Failed to read {} from stdin", kind))?
+    } else {
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {} file: {}", kind, path))?
+    };
+    if contents.trim().is_empty() {
+        let source = if path == STDIN_PATH { "stdin" } else { path };
+        bail!("The {} body from {} is empty", kind, source);
+    }
+    Ok(Some(contents))
+}
+
+/// Previews older than this are removed the next time one is written
+const PREVIEW_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+const PREVIEW_PREFIX: &str = "resend-preview-";
+
+/// Per-user directory for preview files, under the cache dir
+fn preview_dir() -> Result<PathBuf> {
+    ProjectDirs::from("", "", "resend")
+        .map(|dirs| dirs.cache_dir().join("previews"))
+        .ok_or_else(|| anyhow!("Could not determine a cache directory for previews"))
+}
+
+/// Write a preview into `dir`, first clearing out old ones
+///
+/// The file is kept: browser launchers return before the browser has read
+/// it, so it can't be removed when this command ends.
+fn write_preview(dir: &Path, html: &str) -> Result<PathBuf> {
+    remove_stale(dir, PREVIEW_PREFIX, PREVIEW_MAX_AGE);
+    write_kept_in(dir, PREVIEW_PREFIX, ".html", html)
+}
+
+/// Write a preview and hand its path to `open`
+async fn open_preview<F, Fut>(dir: &Path, html: &str, open: F) -> Result<PathBuf>
+where
+    F: FnOnce(PathBuf) -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    let path = write_preview(dir, html)?;
+    open(path.clone()).await?;
+    Ok(path)
+}
+
+/// Open a file with the desktop's default handler
+async fn open_in_browser(path: &Path) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let status = command.arg(path).status().await.with_context(|| {
+        format!(
+            "Failed to open a browser; use --no-open and open {} yourself",
+            path.display()
+        )
+    })?;
+    if !status.success() {
+        bail!(
+            "Failed to open a browser; use --no-open and open {} yourself",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Rebuild the request for a stored email, with optional replacement bodies
+fn resend_request(
+    email: Email,
+    html: &Option<String>,
+    text: &Option<String>,
+) -> Result<SendEmailRequest> {
+    let from = email
+        .from
+        .ok_or_else(|| anyhow!("Email {} has no sender to resend from", email.id))?;
+    let to = email
+        .to
+        .filter(|to| !to.is_empty())
+        .ok_or_else(|| anyhow!("Email {} has no recipients to resend to", email.id))?;
+
+    let (html, text) = if html.is_some() || text.is_some() {
+        (html.clone(), text.clone())
+    } else {
+        (email.html, email.text)
+    };
+    if html.is_none() && text.is_none() {
+        bail!(
+            "The API returned no body for email {}; pass --html or --text to resend it",
+            email.id
+        );
+    }
+
+    Ok(SendEmailRequest {
+        from,
+        to,
+        subject: email.subject.unwrap_or_default(),
+        html,
+        text,
+        cc: email.cc,
+        bcc: email.bcc,
+        reply_to: email.reply_to,
+        scheduled_at: None,
+        headers: None,
+        attachments: None,
+        tags: None,
+    })
+}
+
+/// Read recipient addresses one per line, skipping blanks and `#` comments
+fn read_recipients_file(path: &Path) -> Result<Vec<String>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read recipients file: {}", path.display()))?;
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Combine `--to` addresses with file ones, dropping repeats but keeping order
+fn merge_recipients(inline: &[String], from_file: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    inline
+        .iter()
+        .cloned()
+        .chain(from_file)
+        .filter(|address| seen.insert(address.clone()))
+        .collect()
+}
+
+/// Describe a payload for `emails send --dry-run` in table mode
+fn dry_run_message<T: serde::Serialize>(
+    payload: &T,
+    idempotency_key: Option<&str>,
+) -> Result<String> {
+    let mut message = "Dry run: nothing sent. Would POST to /emails".to_string();
+    if let Some(key) = idempotency_key {
+        message.push_str(&format!(" with Idempotency-Key: {}", key));
+    }
+    message.push_str(&format!(":\n{}", serde_json::to_string_pretty(payload)?));
+    Ok(message)
+}
+
+/// Load the emails for a batch send from a JSON array
+fn load_batch_file(path: &Path) -> Result<Vec<SendEmailRequest>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read batch file: {}", path.display()))?;
+    let reqs: Vec<SendEmailRequest> = serde_json::from_str(&contents).with_context(|| {
+        format!(
+            "Batch file must be a JSON array of emails: {}",
+            path.display()
+        )
+    })?;
+    if reqs.is_empty() {
+        bail!("Batch file has no emails: {}", path.display());
+    }
+    Ok(reqs)
+}
+
+/// Render a Markdown body as HTML (CommonMark, plus tables and strikethrough)
+fn markdown_to_html(source: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, Parser::new_ext(source, options));
+    html
+}
+
+/// First entry to send: `--resume-from`, else the checkpoint, else the start
+fn resume_start(
+    resume_from: Option<usize>,
+    checkpoint: Option<&Checkpoint>,
+    total: usize,
+) -> Result<usize> {
+    let start = resume_from
+        .or(checkpoint.map(|c| c.sent))
+        .unwrap_or_default();
+    if start > total {
+        bail!(
+            "Cannot resume from entry {}; the input has {}",
+            start,
+            total
+        );
+    }
+    if start > 0 {
+        eprintln!("Skipping the first {} of {} entries", start, total);
+    }
+    Ok(start)
+}
+
+/// A CSV row ready to send: its entry index, line, recipient, and request
+type PendingRow = (usize, u64, String, SendEmailRequest);
+
+/// Send rows through the batch endpoint, `MAX_BATCH_SIZE` at a time
+///
+/// Without a checkpoint a failed batch is reported and the rest still send.
+/// With one, progress is saved after each batch and sending stops at the
+/// first failure, so a rerun picks up exactly where this one left off.
+async fn send_in_batches(
+    client: &ResendClient,
+    pending: &[PendingRow],
+    mut checkpoint: Option<&mut Checkpoint>,
+    total: usize,
+) -> Result<Vec<MergeResult>> {
+    let mut results = Vec::new();
+    let mut halted = false;
+    for chunk in pending.chunks(MAX_BATCH_SIZE) {
+        let sent = if halted {
+            Err(anyhow!(
+                "Not sent after an earlier batch failed; rerun with the same --checkpoint"
+            ))
+        } else {
+            let reqs = chunk.iter().map(|(_, _, _, req)| req.clone()).collect();
+            client.send_email_batch(reqs).await
+        };
+
+        if let Some(checkpoint) = checkpoint.as_deref_mut() {
+            match (&sent, chunk.last()) {
+                (Ok(_), Some((index, ..))) => checkpoint.record(index + 1)?,
+                _ => halted = true,
+            }
+        }
+
+        for (position, (_, line, to, _)) in chunk.iter().enumerate() {
+            let (id, error) = match &sent {
+                Ok(sent) => (sent.get(position).map(|r| r.id.clone()), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            results.push(MergeResult {
+                line: *line,
+                to: to.clone(),
+                id,
+                error,
+            });
+        }
+    }
+
+    if let (Some(checkpoint), false) = (checkpoint, halted) {
+        checkpoint.record(total)?;
+    }
+    Ok(results)
+}
+
+/// Load custom headers from a JSON object of string names to string values
+fn load_headers_file(path: &Path) -> Result<HashMap<String, String>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read headers file: {}", path.display()))?;
+    let value: serde_json::Value = serde_json::from_str(&contents)
+        .with_context(|| format!("Headers file is not valid JSON: {}", path.display()))?;
+
+    let serde_json::Value::Object(map) = value else {
+        bail!(
+            "Headers file must contain a JSON object: {}",
+            path.display()
+        );
+    };
+
+    map.into_iter()
+        .map(|(name, value)| match value {
+            serde_json::Value::String(v) => Ok((name, v)),
+            other => bail!(
+                "Header '{}' in {} must be a string, got {}",
+                name,
+                path.display(),
+                other
+            ),
+        })
+        .collect()
+}
+
+/// Column holding the recipient in `send-merge` CSVs
+const MERGE_TO_COLUMN: &str = "email";
+
+/// Column holding the recipient in `send-csv` CSVs
+const CSV_TO_COLUMN: &str = "to";
+
+/// A CSV row's line number and its column -> value map, or why it is malformed
+pub type CsvRow = (u64, Result<HashMap<String, String>>);
+
+/// Read CSV rows as (line number, column -> value) pairs
+///
+/// Malformed rows are kept as errors so callers can report them by line and
+/// carry on with the rest.
+pub fn read_csv_rows(path: &Path, required: &str) -> Result<Vec<CsvRow>> {
+    let mut reader = csv::Reader::from_path(path)
+        .with_context(|| format!("Failed to open CSV file: {}", path.display()))?;
+    let headers = reader.headers()?.clone();
+
+    if !headers.iter().any(|h| h == required) {
+        bail!(
+            "CSV file must have a column named '{}': {}",
+            required,
+            path.display()
+        );
+    }
+
+    Ok(reader
+        .records()
+        .map(|record| match record {
+            Ok(record) => {
+                let line = record.position().map_or(0, |p| p.line());
+                let vars = headers
+                    .iter()
+                    .zip(record.iter())
+                    .map(|(h, v)| (h.to_string(), v.to_string()))
+                    .collect();
+                (line, Ok(vars))
+            }
+            Err(e) => (
+                e.position().map_or(0, |p| p.line()),
+                Err(anyhow!("Malformed row: {}", e)),
+            ),
+        })
+        .collect())
+}
+
+/// Read mail-merge rows as (line number, column -> value) pairs
+fn read_merge_rows(path: &Path) -> Result<Vec<(u64, HashMap<String, String>)>> {
+    read_csv_rows(path, MERGE_TO_COLUMN)?
+        .into_iter()
+        .map(|(line, row)| {
+            let vars = row.with_context(|| format!("Malformed CSV in {}", path.display()))?;
+            Ok((line, vars))
+        })
+        .collect()
+}
+
+/// Fill a template's subject, HTML and text with `vars`
+fn render_template(
+    template: &Template,
+    vars: &HashMap<String, String>,
+) -> Result<(String, Option<String>, Option<String>)> {
+    let render_part = |part: &Option<String>| part.as_deref().map(|p| render(p, vars)).transpose();
+
+    Ok((
+        render(template.subject.as_deref().unwrap_or_default(), vars)?,
+        render_part(&template.html)?,
+        render_part(&template.text)?,
+    ))
+}
+
+/// Render a template for one CSV row, addressed to the row's `to_column`
+/// Render and send one `send-merge` row, redirecting it when asked
+async fn send_merge_row(
+    client: &ResendClient,
+    from: &str,
+    template: &Template,
+    line: u64,
+    vars: HashMap<String, String>,
+    redirect_to: Option<&str>,
+) -> MergeResult {
+    let to = vars.get(MERGE_TO_COLUMN).cloned().unwrap_or_default();
+    let outcome = match merge_request(from, template, &vars, MERGE_TO_COLUMN) {
+        Ok(mut req) => {
+            if let Some(address) = redirect_to {
+                apply_redirect(&mut req, address);
+            }
+            client.send_email(req, None).await.map(|r| r.id)
+        }
+        Err(e) => Err(e),
+    };
+    match outcome {
+        Ok(id) => MergeResult {
+            line,
+            to,
+            id: Some(id),
+            error: None,
+        },
+        Err(e) => MergeResult {
+            line,
+            to,
+            id: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+fn merge_request(
+    from: &str,
+    template: &Template,
+    vars: &HashMap<String, String>,
+    to_column: &str,
+) -> Result<SendEmailRequest> {
+    let to = vars
+        .get(to_column)
+        .filter(|e| !e.trim().is_empty())
+        .ok_or_else(|| anyhow!("Row has no email address"))?;
+
+    let (subject, html, text) = render_template(template, vars)?;
+
+    Ok(SendEmailRequest {
+        from: from.to_string(),
+        to: vec![to.trim().to_string()],
+        subject,
+        html,
+        text,
+        cc: None,
+        bcc: None,
+        reply_to: None,
+        scheduled_at: None,
+        headers: None,
+        attachments: None,
+        tags: None,
+    })
+}
+
+/// Build `Name <address>`, quoting the name if RFC 5322 requires it
+fn format_mailbox(name: Option<&str>, address: &str) -> String {
+    let Some(name) = name.map(str::trim).filter(|name| !name.is_empty()) else {
+        return address.to_string();
+    };
+    let needs_quotes = name
+        .chars()
+        .any(|c| "()<>[]:;@\\,.\"".contains(c) || c.is_control());
+    if needs_quotes {
+        let escaped = name.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("\"{}\" <{}>", escaped, address)
+    } else {
+        format!("{} <{}>", name, address)
+    }
+}
+
+/// Parse `--scheduled-at`, resolving relative times against the local clock
+pub fn parse_scheduled_at(value: &str) -> Result<String, String> {
+    resolve_schedule(value, Local::now()).map_err(|e| e.to_string())
+}
+
+/// Parse `--var KEY=VALUE`, splitting on the first `=`
+fn parse_var(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got '{value}'")),
+    }
+}
+
+/// Set a header, replacing any existing one whose name differs only in case
+///
+/// Header names are case-insensitive, so `--header x-campaign: b` must
+/// replace `X-Campaign` from a headers file rather than send both.
+fn set_header(headers: &mut HashMap<String, String>, name: &str, value: &str) {
+    headers.retain(|existing, _| !existing.eq_ignore_ascii_case(name));
+    headers.insert(name.to_string(), value.to_string());
+}
+
+/// Set a header unless one with the same name in any case is already there
+fn set_header_default(headers: &mut HashMap<String, String>, name: &str, value: &str) {
+    if !headers
+        .keys()
+        .any(|existing| existing.eq_ignore_ascii_case(name))
+    {
+        headers.insert(name.to_string(), value.to_string());
+    }
+}
+
+/// Parse `--header "Name: Value"`, splitting on the first colon
+fn parse_header(value: &str) -> Result<(String, String), String> {
+    let Some((name, value)) = value.split_once(':') else {
+        return Err(format!("expected 'Name: Value', got '{value}'"));
+    };
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("header name before ':' is empty".to_string());
+    }
+    Ok((name.to_string(), value.trim().to_string()))
+}
+
+/// Parse `--tag NAME=VALUE`
+///
+/// Resend only allows ASCII letters, numbers, underscores and dashes in tag
+/// names and values.
+fn parse_tag(value: &str) -> Result<Tag, String> {
+    let Some((name, value)) = value.split_once('=') else {
+        return Err(format!("expected NAME=VALUE, got '{value}'"));
+    };
+    for (part, text) in [("name", name), ("value", value)] {
+        if text.is_empty() {
+            return Err(format!("tag {part} is empty"));
+        }
+        if let Some(c) = text
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-'))
+        {
+            return Err(format!(
+                "tag {part} '{text}' contains '{c}'; only ASCII letters, numbers, \
+                 underscores and dashes are allowed"
+            ));
+        }
+    }
+    Ok(Tag {
+        name: name.to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// Most attachment data Resend accepts on one email
+const MAX_ATTACHMENT_BYTES: u64 = 40 * 1024 * 1024;
+
+/// Read local files into base64 attachments named after the file
+///
+/// `inline` holds `(content ID, path)` pairs for images the HTML shows via
+/// `cid:`. Files are checked against Resend's 40MB cap, individually and
+/// together, before any is read.
+fn load_attachments(paths: &[String], inline: &[(String, String)]) -> Result<Vec<Attachment>> {
+    let files: Vec<(&str, Option<&str>)> = paths
+        .iter()
+        .map(|path| (path.as_str(), None))
+        .chain(
+            inline
+                .iter()
+                .map(|(cid, path)| (path.as_str(), Some(cid.as_str()))),
+        )
+        .collect();
+
+    let mut content_ids = HashSet::new();
+    for (_, cid) in &files {
+        if let Some(cid) = cid {
+            if !content_ids.insert(*cid) {
+                bail!(
+                    "Content ID '{}' is used by more than one --inline image",
+                    cid
+                );
+            }
+        }
+    }
+
+    let mut total = 0;
+    for (path, _) in &files {
+        let size = fs::metadata(path)
+            .with_context(|| format!("Failed to read attachment: {}", path))?
+            .len();
+        total += size;
+        if total > MAX_ATTACHMENT_BYTES {
+            bail!(
+                "Attachments exceed Resend's {}MB limit at {} ({:.1}MB so far). \
+                 Host large files and use --attach-url instead.",
+                MAX_ATTACHMENT_BYTES / (1024 * 1024),
+                path,
+                total as f64 / (1024.0 * 1024.0)
+            );
+        }
+    }
+
+    files
+        .into_iter()
+        .map(|(path, cid)| {
+            let bytes =
+                fs::read(path).with_context(|| format!("Failed to read attachment: {}", path))?;
+            let filename = Path::new(path)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned());
+            Ok(Attachment {
+                filename,
+                path: None,
+                content: Some(BASE64.encode(bytes)),
+                content_id: cid.map(str::to_string),
+                disposition: cid.map(|_| "inline".to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Parse `--inline [CID=]PATH`; the content ID defaults to the file name
+fn parse_inline(value: &str) -> Result<(String, String), String> {
+    let (cid, path) = match value.split_once('=') {
+        Some((cid, path)) => (cid.trim().to_string(), path.trim()),
+        None => {
+            let name = Path::new(value)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .ok_or_else(|| format!("'{value}' is not a file path"))?;
+            (name, value.trim())
+        }
+    };
+    if cid.is_empty() {
+        return Err("content ID before '=' is empty".to_string());
+    }
+    if cid.contains(|c: char| c.is_whitespace() || c == '<' || c == '>') {
+        return Err(format!(
+            "content ID '{cid}' can't contain spaces or angle brackets"
+        ));
+    }
+    Ok((cid, path.to_string()))
+}
+
+/// Parse `--attach-url [NAME=]URL`
+///
+/// Without a name, the filename is taken from the URL's last path segment.
+fn parse_attach_url(value: &str) -> Result<Attachment, String> {
+    let (name, url) = match value.split_once('=') {
+        Some((name, url)) if !name.contains("://") => (Some(name.trim()), url.trim()),
+        _ => (None, value.trim()),
+    };
+
+    let parsed = Url::parse(url).map_err(|e| format!("invalid URL '{url}': {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(format!("'{url}' must be an http(s) URL with a host"));
+    }
+
+    let filename = match name {
+        Some("") => return Err("attachment name before '=' is empty".to_string()),
+        Some(name) => Some(name.to_string()),
+        None => parsed
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| segment.to_string()),
+    };
+
+    Ok(Attachment {
+        filename,
+        path: Some(url.to_string()),
+        content: None,
+        content_id: None,
+        disposition: None,
+    })
+}
+
+/// Header recording who a redirected email was originally addressed to
+const ORIGINAL_TO_HEADER: &str = "X-Original-To";
+
+/// Replace every recipient with `address`, keeping the originals in a header
+fn apply_redirect(req: &mut SendEmailRequest, address: &str) {
+    let original: Vec<String> = req
+        .to
+        .drain(..)
+        .chain(req.cc.take().unwrap_or_default())
+        .chain(req.bcc.take().unwrap_or_default())
+        .collect();
+
+    req.to = vec![address.to_string()];
+    req.headers
+        .get_or_insert_with(HashMap::new)
+        .insert(ORIGINAL_TO_HEADER.to_string(), original.join(", "));
+}
+
+/// One copy of `req` per `to` address, each addressed to that recipient alone
+///
+/// CC, BCC and every other field are kept on each copy.
+fn split_by_recipient(req: &SendEmailRequest) -> Vec<SendEmailRequest> {
+    req.to
+        .iter()
+        .map(|to| SendEmailRequest {
+            to: vec![to.clone()],
+            ..req.clone()
+        })
+        .collect()
+}
+
+/// Ask before sending to more than `threshold` recipients
+///
+/// Without a terminal to ask on, the send is refused unless `--yes` is given.
+fn confirm_recipients(count: usize, threshold: usize, yes: bool) -> Result<()> {
+    if yes || count <= threshold {
+        return Ok(());
+    }
+
+    if !std::io::stdin().is_terminal() {
+        bail!(
+            "Refusing to send to {} recipients (threshold {}) without confirmation. \
+             Pass --yes to send anyway.",
+            count,
+            threshold
+        );
+    }
+
+    let proceed = Confirm::new()
+        .with_prompt(format!("Send this email to {} recipients?", count))
+        .default(false)
+        .interact()?;
+
+    if !proceed {
+        bail!("Send cancelled");
+    }
+
+    Ok(())
+}
+
+/// Status the API reports for emails that have not been sent yet
+const SCHEDULED_STATUS: &str = "scheduled";
+
+/// Whether a `last_event` is final: `Some(true)` once delivered,
+/// `Some(false)` if it never will be, `None` while still in flight
+fn delivery_outcome(event: &str) -> Option<bool> {
+    let status = EmailStatus::from_str(event, true).ok()?;
+    match status {
+        EmailStatus::Delivered => Some(true),
+        EmailStatus::Bounced
+        | EmailStatus::Complained
+        | EmailStatus::Failed
+        | EmailStatus::Canceled => Some(false),
+        _ => None,
+    }
+}
+
+/// Keep only emails whose latest event matches one of the given statuses
+fn filter_by_status(emails: Vec<Email>, statuses: &[EmailStatus]) -> Vec<Email> {
+    if statuses.is_empty() {
+        return emails;
+    }
+    emails
+        .into_iter()
+        .filter(|email| {
+            email.last_event.as_deref().is_some_and(|event| {
+                statuses
+                    .iter()
+                    .any(|status| status.as_str().eq_ignore_ascii_case(event))
+            })
+        })
+        .collect()
+}
+
+/// Keep emails still waiting to send after `now`, soonest first
+fn scheduled_emails(emails: Vec<Email>, now: DateTime<Utc>) -> Vec<Email> {
+    let mut pending: Vec<(DateTime<Utc>, Email)> = emails
+        .into_iter()
+        .filter(|e| e.last_event.as_deref() == Some(SCHEDULED_STATUS))
+        .filter_map(|e| {
+            let at = e.scheduled_at.as_deref().and_then(parse_timestamp)?;
+            (at > now).then_some((at, e))
+        })
+        .collect();
+
+    pending.sort_by_key(|(at, _)| *at);
+    pending.into_iter().map(|(_, e)| e).collect()
+}
+
+/// Fail with a clear reason when an email can no longer be cancelled or updated
+fn ensure_scheduled(email: &Email, now: DateTime<Utc>, action: &str) -> Result<()> {
+    match email.last_event.as_deref() {
+        Some(SCHEDULED_STATUS) => {}
+        Some(status) => bail!(
+            "Email {} is already {}, cannot {}",
+            email.id,
+            status,
+            action
+        ),
+        None => bail!("Email {} has no status yet, cannot {}", email.id, action),
+    }
+
+    if let Some(at) = email.scheduled_at.as_deref().and_then(parse_timestamp) {
+        if at <= now {
+            bail!(
+                "Email {} was due to send at {}, cannot {}",
+                email.id,
+                at.to_rfc3339(),
+                action
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email(id: &str, last_event: &str, scheduled_at: Option<&str>) -> Email {
+        Email {
+            last_event: Some(last_event.to_string()),
+            scheduled_at: scheduled_at.map(|s| s.to_string()),
+            ..Email::fixture(id)
+        }
+    }
+
+    #[test]
+    fn test_load_headers_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("headers.json");
+        fs::write(
+            &path,
+            r#"{"List-Unsubscribe": "<mailto:unsub@example.com>", "X-Campaign": "spring"}"#,
+        )
+        .unwrap();
+
+        let headers = load_headers_file(&path).unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["X-Campaign"], "spring");
+    }
+
+    #[test]
+    fn test_set_header_replaces_any_case() {
+        let mut headers = HashMap::from([
+            ("X-Campaign".to_string(), "spring".to_string()),
+            ("X-Other".to_string(), "kept".to_string()),
+        ]);
+        set_header(&mut headers, "x-campaign", "summer");
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["x-campaign"], "summer");
+        assert_eq!(headers["X-Other"], "kept");
+    }
+
+    #[test]
+    fn test_priority_keeps_explicit_headers() {
+        let mut headers = HashMap::from([("x-priority".to_string(), "2".to_string())]);
+        for (name, value) in Priority::High.headers() {
+            set_header_default(&mut headers, name, value);
+        }
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["x-priority"], "2");
+        assert_eq!(headers["Importance"], "high");
+    }
+
+    #[test]
+    fn test_load_headers_file_rejects_nested_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("headers.json");
+        fs::write(&path, r#"{"X-Meta": {"nested": true}}"#).unwrap();
+        assert!(load_headers_file(&path).is_err());
+
+        fs::write(&path, r#"["X-Campaign"]"#).unwrap();
+        assert!(load_headers_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_apply_redirect_replaces_all_recipients() {
+        let mut req = SendEmailRequest {
+            from: "me@example.com".to_string(),
+            to: vec!["a@example.com".to_string(), "b@example.com".to_string()],
+            subject: "Hi".to_string(),
+            html: None,
+            text: Some("Hello".to_string()),
+            cc: Some(vec!["c@example.com".to_string()]),
+            bcc: Some(vec!["d@example.com".to_string()]),
+            reply_to: None,
+            scheduled_at: None,
+            headers: None,
+            attachments: None,
+            tags: None,
+        };
+
+        apply_redirect(&mut req, "qa@example.com");
+
+        assert_eq!(req.to, vec!["qa@example.com"]);
+        assert!(req.cc.is_none());
+        assert!(req.bcc.is_none());
+        assert_eq!(
+            req.headers.unwrap()[ORIGINAL_TO_HEADER],
+            "a@example.com, b@example.com, c@example.com, d@example.com"
+        );
+    }
+
+    #[test]
+    fn test_resolve_body_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("body.html");
+        fs::write(&path, "<p>\"Hi\"</p>\n").unwrap();
+
+        let body = resolve_body(&None, path.to_str(), "HTML").unwrap();
+        assert_eq!(body.as_deref(), Some("<p>\"Hi\"</p>\n"));
+
+        let inline = Some("<p>Inline</p>".to_string());
+        assert_eq!(resolve_body(&inline, None, "HTML").unwrap(), inline);
+
+        let empty = dir.path().join("empty.txt");
+        fs::write(&empty, "\n").unwrap();
+        let err = resolve_body(&None, empty.to_str(), "text").unwrap_err();
+        assert!(err.to_string().contains("is empty"));
+
+        let missing = dir.path().join("missing.html");
+        let err = resolve_body(&None, missing.to_str(), "HTML").unwrap_err();
+        assert!(err.to_string().starts_with("Failed to read HTML file:"));
+    }
+
+    #[test]
+    fn test_split_by_recipient() {
+        let req = SendEmailRequest {
+            from: "me@example.com".to_string(),
+            to: vec!["a@example.com".to_string(), "b@example.com".to_string()],
+            subject: "Hi".to_string(),
+            html: None,
+            text: Some("Hello".to_string()),
+            cc: None,
+            bcc: Some(vec!["audit@example.com".to_string()]),
+            reply_to: None,
+            scheduled_at: None,
+            headers: None,
+            attachments: None,
+            tags: None,
+        };
+
+        let copies = split_by_recipient(&req);
+
+        assert_eq!(copies.len(), 2);
+        assert_eq!(copies[0].to, vec!["a@example.com"]);
+        assert_eq!(copies[1].to, vec!["b@example.com"]);
+        assert_eq!(copies[1].bcc, req.bcc);
+        assert_eq!(copies[1].text.as_deref(), Some("Hello"));
+    }
+
+    #[test]
+    fn test_read_recipients_file_and_merge() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("list.txt");
+        fs::write(
+            &path,
+            "# team\nb@example.com\n\n  c@example.com  \na@example.com\nb@example.com\n",
+        )
+        .unwrap();
+
+        let from_file = read_recipients_file(&path).unwrap();
+        assert_eq!(
+            from_file,
+            vec![
+                "b@example.com",
+                "c@example.com",
+                "a@example.com",
+                "b@example.com"
+            ]
+        );
+
+        let merged = merge_recipients(&["a@example.com".to_string()], from_file);
+        assert_eq!(
+            merged,
+            vec!["a@example.com", "b@example.com", "c@example.com"]
+        );
+
+        let missing = dir.path().join("missing.txt");
+        assert!(read_recipients_file(&missing).is_err());
+    }
+
+    #[test]
+    fn test_dry_run_message_shows_payload() {
+        let req = SendEmailRequest {
+            from: "me@example.com".to_string(),
+            to: vec!["a@example.com".to_string()],
+            subject: "Hi".to_string(),
+            html: None,
+            text: Some("Hello".to_string()),
+            cc: None,
+            bcc: None,
+            reply_to: None,
+            scheduled_at: None,
+            headers: None,
+            attachments: None,
+            tags: None,
+        };
+
+        let message = dry_run_message(&req, Some("key-1")).unwrap();
+        let (label, payload) = message.split_once('\n').unwrap();
+        assert_eq!(
+            label,
+            "Dry run: nothing sent. Would POST to /emails with Idempotency-Key: key-1:"
+        );
+        let payload: serde_json::Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(payload, serde_json::to_value(&req).unwrap());
+    }
+
+    #[test]
+    fn test_read_merge_rows_and_render() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recipients.csv");
+        fs::write(
+            &path,
+            "email,name\nada@example.com,Ada\n\"grace@example.com\",\"Hopper, Grace\"\n",
+        )
+        .unwrap();
+
+        let rows = read_merge_rows(&path).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].0, 2);
+        assert_eq!(rows[1].1["name"], "Hopper, Grace");
+
+        let template = Template {
+            id: "tmpl-1".to_string(),
+            name: "Welcome".to_string(),
+            subject: Some("Hi {{name}}".to_string()),
+            html: Some("<p>Hello {{name}}</p>".to_string()),
+            text: None,
+            created_at: None,
+        };
+        let req = merge_request("me@example.com", &template, &rows[1].1, MERGE_TO_COLUMN).unwrap();
+        assert_eq!(req.to, vec!["grace@example.com"]);
+        assert_eq!(req.subject, "Hi Hopper, Grace");
+        assert_eq!(req.html.as_deref(), Some("<p>Hello Hopper, Grace</p>"));
+    }
+
+    #[test]
+    fn test_read_csv_rows_keeps_malformed_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recipients.csv");
+        fs::write(
+            &path,
+            "to,name\nada@example.com,Ada\ngrace@example.com,Grace,extra\nalan@example.com,Alan\n",
+        )
+        .unwrap();
+
+        let rows = read_csv_rows(&path, CSV_TO_COLUMN).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].1.is_ok());
+        assert_eq!(rows[1].0, 3);
+        assert!(rows[1]
+            .1
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .starts_with("Malformed row"));
+        assert_eq!(rows[2].1.as_ref().unwrap()["name"], "Alan");
+
+        assert!(read_merge_rows(&path).is_err());
+    }
+
+    #[test]
+    fn test_read_merge_rows_requires_email_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recipients.csv");
+        fs::write(&path, "name\nAda\n").unwrap();
+        assert!(read_merge_rows(&path).is_err());
+    }
+
+    #[test]
+    fn test_confirm_recipients_below_threshold() {
+        assert!(confirm_recipients(3, DEFAULT_CONFIRM_THRESHOLD, false).is_ok());
+        assert!(confirm_recipients(50, 50, false).is_ok());
+    }
+
+    #[test]
+    fn test_confirm_recipients_yes_skips_check() {
+        assert!(confirm_recipients(500, 50, true).is_ok());
+    }
+
+    #[test]
+    fn test_format_mailbox() {
+        assert_eq!(format_mailbox(None, "a@example.com"), "a@example.com");
+        assert_eq!(format_mailbox(Some("  "), "a@example.com"), "a@example.com");
+        assert_eq!(
+            format_mailbox(Some("Acme Support"), "a@example.com"),
+            "Acme Support <a@example.com>"
+        );
+        assert_eq!(
+            format_mailbox(Some("Lovelace, Ada"), "a@example.com"),
+            "\"Lovelace, Ada\" <a@example.com>"
+        );
+        assert_eq!(
+            format_mailbox(Some("Ada \"The Countess\" L."), "a@example.com"),
+            "\"Ada \\\"The Countess\\\" L.\" <a@example.com>"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_merge_row_honours_redirect() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/emails"))
+            .and(body_partial_json(serde_json::json!({
+                "to": ["qa@example.com"],
+                "headers": {"X-Original-To": "ada@example.com"}
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"id": "email-1"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = ResendClient::builder("re_test")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let template = Template {
+            id: "tmpl-1".to_string(),
+            name: "Welcome".to_string(),
+            subject: Some("Hi {{name}}".to_string()),
+            html: Some("<p>Hello {{name}}</p>".to_string()),
+            text: None,
+            created_at: None,
+        };
+        let vars = HashMap::from([
+            (MERGE_TO_COLUMN.to_string(), "ada@example.com".to_string()),
+            ("name".to_string(), "Ada".to_string()),
+        ]);
+
+        let result = send_merge_row(
+            &client,
+            "me@example.com",
+            &template,
+            2,
+            vars,
+            Some("qa@example.com"),
+        )
+        .await;
+        assert_eq!(result.error, None);
+        assert_eq!(result.id.as_deref(), Some("email-1"));
+        assert_eq!(result.to, "ada@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_preview_outlives_the_launcher() {
+        let dir = tempfile::tempdir().unwrap();
+        // Like xdg-open, the launcher returns before anything reads the file
+        let path = open_preview(dir.path(), "<p>Hi</p>", |_| async { Ok(()) })
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "<p>Hi</p>");
+        assert!(path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with(PREVIEW_PREFIX));
+    }
+
+    #[test]
+    fn test_resume_start() {
+        assert_eq!(resume_start(None, None, 5).unwrap(), 0);
+        assert_eq!(resume_start(Some(3), None, 5).unwrap(), 3);
+        assert_eq!(resume_start(Some(5), None, 5).unwrap(), 5);
+        let err = resume_start(Some(6), None, 5).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot resume from entry 6; the input has 5"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_in_batches_resumes_from_checkpoint() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("recipients.csv");
+        let checkpoint_path = dir.path().join("recipients.checkpoint");
+        fs::write(&input, "to\n").unwrap();
+
+        let req: SendEmailRequest = serde_json::from_value(serde_json::json!({
+            "from": "news@example.com",
+            "to": ["someone@example.com"],
+            "subject": "News",
+            "text": "Hello"
+        }))
+        .unwrap();
+        let rows: Vec<PendingRow> = (0..150)
+            .map(|i| (i, i as u64 + 2, format!("u{i}@example.com"), req.clone()))
+            .collect();
+        let ids = |n: usize| {
+            let data: Vec<_> = (0..n)
+                .map(|i| serde_json::json!({ "id": format!("email-{i}") }))
+                .collect();
+            serde_json::json!({ "data": data })
+        };
+
+        // First run: the second batch fails part-way through the file
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/emails/batch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(ids(100)))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/emails/batch"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        let client = ResendClient::builder("re_test")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let mut checkpoint = Checkpoint::load(&checkpoint_path, &input).unwrap();
+        let start = resume_start(None, Some(&checkpoint), rows.len()).unwrap();
+        let results = send_in_batches(&client, &rows[start..], Some(&mut checkpoint), rows.len())
+            .await
+            .unwrap();
+        assert!(results[..100].iter().all(|r| r.error.is_none()));
+        assert!(results[100..].iter().all(|r| r.error.is_some()));
+
+        // Rerun: only the rows after the checkpoint go out, in one batch
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/emails/batch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(ids(50)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = ResendClient::builder("re_test")
+            .base_url(server.uri())
+            .build()
+            .unwrap();
+
+        let mut checkpoint = Checkpoint::load(&checkpoint_path, &input).unwrap();
+        let start = resume_start(None, Some(&checkpoint), rows.len()).unwrap();
+        assert_eq!(start, 100);
+        let results = send_in_batches(&client, &rows[start..], Some(&mut checkpoint), rows.len())
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 50);
+        assert_eq!(results[0].to, "u100@example.com");
+        assert!(results.iter().all(|r| r.error.is_none()));
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body.as_array().unwrap().len(), 50);
+        assert_eq!(
+            Checkpoint::load(&checkpoint_path, &input).unwrap().sent,
+            150
+        );
+    }
+
+    #[test]
+    fn test_resend_request() {
+        let mut original = email("email-1", "bounced", None);
+        original.from = Some("me@example.com".to_string());
+        original.to = Some(vec!["a@example.com".to_string()]);
+        original.subject = Some("Hi".to_string());
+        original.bcc = Some(vec!["audit@example.com".to_string()]);
+
+        let err = resend_request(original.clone(), &None, &None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The API returned no body for email email-1; pass --html or --text to resend it"
+        );
+
+        let req = resend_request(original.clone(), &None, &Some("Again".to_string())).unwrap();
+        assert_eq!(req.to, vec!["a@example.com"]);
+        assert_eq!(req.subject, "Hi");
+        assert_eq!(req.bcc, original.bcc);
+        assert_eq!(req.text.as_deref(), Some("Again"));
+
+        original.html = Some("<p>Stored</p>".to_string());
+        original.text = Some("Stored".to_string());
+        let req = resend_request(original.clone(), &None, &None).unwrap();
+        assert_eq!(req.html.as_deref(), Some("<p>Stored</p>"));
+
+        let req = resend_request(original, &Some("<p>New</p>".to_string()), &None).unwrap();
+        assert_eq!(req.html.as_deref(), Some("<p>New</p>"));
+        assert_eq!(req.text, None);
+    }
+
+    #[test]
+    fn test_delivery_outcome() {
+        assert_eq!(delivery_outcome("delivered"), Some(true));
+        assert_eq!(delivery_outcome("bounced"), Some(false));
+        assert_eq!(delivery_outcome("Complained"), Some(false));
+        assert_eq!(delivery_outcome("sent"), None);
+        assert_eq!(delivery_outcome("delivery_delayed"), None);
+        assert_eq!(delivery_outcome(""), None);
+        assert_eq!(delivery_outcome("something_new"), None);
+    }
+
+    #[test]
+    fn test_filter_by_status() {
+        let emails = vec![
+            email("a", "delivered", None),
+            email("b", "Bounced", None),
+            email("c", "delivery_delayed", None),
+        ];
+        assert_eq!(filter_by_status(emails.clone(), &[]).len(), 3);
+
+        let ids: Vec<String> = filter_by_status(
+            emails,
+            &[EmailStatus::Bounced, EmailStatus::DeliveryDelayed],
+        )
+        .into_iter()
+        .map(|e| e.id)
+        .collect();
+        assert_eq!(ids, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_scheduled_emails_filters_and_sorts() {
+        let now = parse_timestamp("2025-01-15T12:00:00Z").unwrap();
+        let emails = vec![
+            email("later", "scheduled", Some("2025-01-20T09:00:00Z")),
+            email("past", "scheduled", Some("2025-01-10T09:00:00Z")),
+            email("sent", "delivered", Some("2025-01-18T09:00:00Z")),
+            email("sooner", "scheduled", Some("2025-01-16 08:00:00.000+00")),
+            email("unscheduled", "scheduled", None),
+        ];
+
+        let ids: Vec<String> = scheduled_emails(emails, now)
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec!["sooner", "later"]);
+    }
+
+    #[test]
+    fn test_ensure_scheduled() {
+        let now = parse_timestamp("2025-01-15T12:00:00Z").unwrap();
+
+        assert!(ensure_scheduled(
+            &email("e1", "scheduled", Some("2025-01-16T09:00:00Z")),
+            now,
+            "cancel"
+        )
+        .is_ok());
+
+        let err = ensure_scheduled(&email("e2", "delivered", None), now, "cancel").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Email e2 is already delivered, cannot cancel"
+        );
+
+        let err = ensure_scheduled(
+            &email("e3", "scheduled", Some("2025-01-15T11:00:00Z")),
+            now,
+            "update",
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("Email e3 was due to send at"));
+    }
+
+    #[test]
+    fn test_load_batch_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("batch.json");
+        fs::write(
+            &path,
+            r#"[
+                {"from": "me@example.com", "to": ["a@example.com"], "subject": "A", "text": "1"},
+                {"from": "me@example.com", "to": ["b@example.com"], "subject": "B", "html": "<p>2</p>",
+                 "tags": [{"name": "kind", "value": "digest"}]}
+            ]"#,
+        )
+        .unwrap();
+
+        let reqs = load_batch_file(&path).unwrap();
+        assert_eq!(reqs.len(), 2);
+        assert_eq!(reqs[1].to, vec!["b@example.com"]);
+        assert_eq!(reqs[1].tags.as_ref().unwrap()[0].value, "digest");
+
+        fs::write(&path, "[]").unwrap();
+        assert!(load_batch_file(&path).is_err());
+
+        fs::write(&path, r#"[{"from": "me@example.com", "too": ["typo"]}]"#).unwrap();
+        assert!(load_batch_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_template_id_excludes_inline_content() {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct Cli {
+            #[command(subcommand)]
+            command: EmailsCommands,
+        }
+
+        let base = [
+            "resend",
+            "send",
+            "--from",
+            "me@example.com",
+            "--to",
+            "a@example.com",
+        ];
+        let parse = |extra: &[&str]| Cli::try_parse_from(base.iter().chain(extra));
+
+        assert!(parse(&["--template-id", "tmpl-1", "--var", "name=Ada"]).is_ok());
+        assert!(parse(&["--template-id", "tmpl-1", "--html", "<p>Hi</p>"]).is_err());
+        assert!(parse(&["--template-id", "tmpl-1", "--subject", "Hi"]).is_err());
+        assert!(parse(&["--var", "name=Ada", "--subject", "Hi"]).is_err());
+        assert!(parse(&["--text", "Hi"]).is_err());
+
+        assert_eq!(
+            parse_var("greeting=a=b").unwrap(),
+            ("greeting".to_string(), "a=b".to_string())
+        );
+        assert!(parse_var("=x").is_err());
+    }
+
+    #[test]
+    fn test_parse_tag() {
+        assert_eq!(
+            parse_tag("category=order_confirmation").unwrap(),
+            Tag {
+                name: "category".to_string(),
+                value: "order_confirmation".to_string(),
+            }
+        );
+        assert!(parse_tag("category").is_err());
+        assert!(parse_tag("=x").is_err());
+        let err = parse_tag("user id=42").unwrap_err();
+        assert!(err.contains("contains ' '"));
+    }
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            parse_header("List-Unsubscribe: <https://example.com/u?a=1>").unwrap(),
+            (
+                "List-Unsubscribe".to_string(),
+                "<https://example.com/u?a=1>".to_string()
+            )
+        );
+        assert!(parse_header("X-Entity-Ref-ID 123").is_err());
+        assert!(parse_header(": value").is_err());
+    }
+
+    #[test]
+    fn test_load_attachments_encodes_and_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, "hello").unwrap();
+
+        let attachments = load_attachments(&[path.to_str().unwrap().to_string()], &[]).unwrap();
+        assert_eq!(
+            attachments,
+            vec![Attachment {
+                filename: Some("notes.txt".to_string()),
+                path: None,
+                content: Some("aGVsbG8=".to_string()),
+                content_id: None,
+                disposition: None,
+            }]
+        );
+
+        let missing = dir.path().join("missing.pdf");
+        assert!(load_attachments(&[missing.to_str().unwrap().to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn test_load_inline_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logo.png");
+        fs::write(&path, "hello").unwrap();
+        let path = path.to_str().unwrap();
+
+        let (cid, file) = parse_inline(path).unwrap();
+        assert_eq!(cid, "logo.png");
+        assert_eq!(parse_inline(&format!("brand={}", path)).unwrap().0, "brand");
+        assert!(parse_inline("=logo.png").is_err());
+        assert!(parse_inline("my logo=logo.png").is_err());
+
+        let attachments = load_attachments(&[], &[(cid, file.clone())]).unwrap();
+        assert_eq!(attachments[0].content_id.as_deref(), Some("logo.png"));
+        assert_eq!(attachments[0].disposition.as_deref(), Some("inline"));
+        assert_eq!(attachments[0].content.as_deref(), Some("aGVsbG8="));
+
+        let twice = [("x".to_string(), file.clone()), ("x".to_string(), file)];
+        let err = load_attachments(&[], &twice).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Content ID 'x' is used by more than one --inline image"
+        );
+    }
+
+    #[test]
+    fn test_load_attachments_rejects_over_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.bin");
+        let file = fs::File::create(&path).unwrap();
+        file.set_len(MAX_ATTACHMENT_BYTES + 1).unwrap();
+
+        let err = load_attachments(&[path.to_str().unwrap().to_string()], &[]).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Attachments exceed Resend's 40MB limit"));
+    }
+
+    #[test]
+    fn test_parse_attach_url() {
+        assert_eq!(
+            parse_attach_url("https://cdn.example.com/files/report.pdf?v=2").unwrap(),
+            Attachment {
+                filename: Some("report.pdf".to_string()),
+                path: Some("https://cdn.example.com/files/report.pdf?v=2".to_string()),
+                content: None,
+                content_id: None,
+                disposition: None,
+            }
+        );
+        assert_eq!(
+            parse_attach_url("Q3.pdf=https://cdn.example.com/dl?id=7")
+                .unwrap()
+                .filename
+                .as_deref(),
+            Some("Q3.pdf")
+        );
+        assert!(parse_attach_url("ftp://example.com/a.pdf").is_err());
+        assert!(parse_attach_url("not a url").is_err());
+        assert!(parse_attach_url("=https://example.com/a.pdf").is_err());
+    }
+}
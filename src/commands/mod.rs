@@ -2,15 +2,31 @@
 // ABOUTME: Each submodule handles a resource type (emails, domains, etc.).
 
 pub mod api_keys;
+pub mod audiences;
+pub mod broadcasts;
 pub mod config;
+pub mod contacts;
 pub mod domains;
 pub mod emails;
 pub mod templates;
+pub mod whoami;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use clap::ArgAction;
+use dialoguer::Confirm;
+use serde_json::json;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::time::Duration;
 
-use crate::config::Config;
-use crate::types::OutputFormat;
+use crate::client::{
+    ResendClient, RetryPolicy, StatusMatch, BASE_URL, DEFAULT_CONNECT_TIMEOUT_SECS,
+    DEFAULT_TIMEOUT_SECS,
+};
+use crate::concurrency::DEFAULT_CONCURRENCY;
+use crate::config::{ApiKeySource, Config};
+use crate::formatters::STDOUT_PATH;
+use crate::types::{ColorChoice, OutputFormat};
 
 /// Common arguments shared across commands
 #[derive(Debug, Clone, clap::Args)]
@@ -19,37 +35,487 @@ pub struct CommonArgs {
     #[arg(long)]
     pub json: bool,
 
-    /// Output file path
+    /// Output format: table, json, ndjson, ids, toml (lists become an `items` array), or csv
+    #[arg(long, value_enum, conflicts_with_all = ["json", "only_ids"])]
+    pub format: Option<OutputFormat>,
+
+    /// Print only resource IDs, one per line
+    #[arg(long, alias = "ids")]
+    pub only_ids: bool,
+
+    /// Print only resource IDs and no success messages; overrides --verbose
+    #[arg(short, long, conflicts_with_all = ["json", "format", "select"])]
+    pub quiet: bool,
+
+    /// Print only this dotted field path from each result (e.g. last_event)
+    #[arg(long, value_name = "PATH")]
+    pub select: Option<String>,
+
+    /// Limit JSON output to these comma-separated field paths
+    #[arg(long, value_name = "FIELDS", value_delimiter = ',')]
+    pub fields: Option<Vec<String>>,
+
+    /// Show only these comma-separated table/CSV columns, in this order (e.g. ID,STATUS)
+    #[arg(long, value_name = "COLUMNS", value_delimiter = ',')]
+    pub columns: Option<Vec<String>>,
+
+    /// Print each result with this template, e.g. '{ID}: {STATUS}' (table column names)
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        conflicts_with_all = ["json", "format", "only_ids", "quiet", "select"]
+    )]
+    pub output_template: Option<String>,
+
+    /// Truncate table cells to N display columns, ending in "…"
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub max_width: Option<usize>,
+
+    /// Leave out the header row of table and CSV output
+    #[arg(long)]
+    pub no_headers: bool,
+
+    /// Emit JSON on a single line instead of pretty-printed
+    #[arg(long)]
+    pub compact: bool,
+
+    /// Output file path (`-` for stdout)
     #[arg(short, long)]
     pub output: Option<String>,
 
+    /// Write formatted output to stderr instead of stdout
+    #[arg(long, conflicts_with = "output")]
+    pub output_stderr: bool,
+
+    /// Color table output: auto, always, or never
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
     /// Profile name
     #[arg(long)]
     pub profile: Option<String>,
 
+    /// API key for this command, overriding RESEND_API_KEY and the profile
+    #[arg(long, value_name = "KEY")]
+    pub api_key: Option<String>,
+
+    /// Read the API key from this file (e.g. a mounted secret)
+    #[arg(long, value_name = "PATH")]
+    pub api_key_file: Option<PathBuf>,
+
     /// Verbose output
     #[arg(short, long)]
     pub verbose: bool,
+
+    /// Request timeout in seconds for this command (default 30)
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout: Option<u64>,
+
+    /// Connection timeout in seconds for this command (default 10)
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub connect_timeout: Option<u64>,
+
+    /// Show errors as friendly explanations with suggestions
+    #[arg(long)]
+    pub pretty_errors: bool,
+
+    /// Report errors as JSON on stderr (implied by JSON output)
+    #[arg(long)]
+    pub json_errors: bool,
+
+    /// Wait for operations the API accepts asynchronously (202) to finish
+    #[arg(long)]
+    pub wait: bool,
+
+    /// Fail if the API returns fields the CLI doesn't know about
+    #[arg(long)]
+    pub strict_json: bool,
+
+    /// Extra trusted root certificate (PEM), e.g. for an intercepting proxy
+    #[arg(long, value_name = "PATH")]
+    pub ca_cert: Option<PathBuf>,
+
+    /// Proxy for API requests, overriding HTTPS_PROXY/HTTP_PROXY
+    #[arg(long, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// Disable TLS certificate verification (insecure, debugging only)
+    #[arg(long)]
+    pub insecure: bool,
+
+    /// Seconds to keep idle connections open for reuse (default 90)
+    #[arg(long, value_name = "SECONDS")]
+    pub pool_idle_timeout: Option<u64>,
+
+    /// Idle connections to keep open to the API (default 16)
+    #[arg(long, value_name = "N")]
+    pub pool_max_idle: Option<usize>,
+
+    /// Maximum requests in flight for commands that fan out
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = DEFAULT_CONCURRENCY,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub concurrency: usize,
+
+    /// Retry failed requests up to this many times (0 disables retries)
+    #[arg(
+        long,
+        visible_alias = "max-retries",
+        value_name = "N",
+        default_value_t = 3
+    )]
+    pub retries: u32,
+
+    /// Statuses or classes that trigger a retry (e.g. 429,5xx)
+    #[arg(
+        long,
+        value_name = "STATUSES",
+        value_delimiter = ',',
+        default_value = "429,5xx"
+    )]
+    pub retry_on: Vec<StatusMatch>,
+
+    /// Initial delay between retries in seconds, doubling each attempt
+    #[arg(long, value_name = "SECONDS", default_value_t = 1)]
+    pub retry_delay: u64,
+
+    /// Longest delay between retries in seconds; a server Retry-After is
+    /// waited out in full up to 5 minutes, and longer ones fail the request
+    #[arg(long, value_name = "SECONDS", default_value_t = 30)]
+    pub retry_max_delay: u64,
+
+    /// Print how each setting was resolved (JSON, to stderr) before running
+    #[arg(long)]
+    pub explain_config: bool,
+}
+
+/// Options accepted before the subcommand, e.g. `resend --json emails list`
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct GlobalArgs {
+    /// Output format
+    #[arg(long)]
+    pub json: bool,
+
+    /// Output format: table, json, ndjson, ids, toml, or csv
+    #[arg(long, value_enum, conflicts_with = "json")]
+    pub format: Option<OutputFormat>,
+
+    /// Output file path (`-` for stdout)
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    /// Profile name
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Verbose output
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+impl GlobalArgs {
+    /// True when any option was given before the subcommand
+    pub fn is_set(&self) -> bool {
+        self.json
+            || self.verbose
+            || self.format.is_some()
+            || self.output.is_some()
+            || self.profile.is_some()
+    }
 }
 
 impl CommonArgs {
+    /// Fill in options given before the subcommand; ones given after it win
+    pub fn inherit(&mut self, global: &GlobalArgs) {
+        // Any format chosen after the subcommand replaces the global one,
+        // including a template or a selected field, which print their own way
+        let chose_format = self.json
+            || self.format.is_some()
+            || self.only_ids
+            || self.quiet
+            || self.output_template.is_some()
+            || self.select.is_some();
+        if !chose_format {
+            self.json = global.json;
+            self.format = global.format;
+        }
+        self.verbose |= global.verbose;
+        if self.output.is_none() {
+            self.output = global.output.clone();
+        }
+        if self.profile.is_none() {
+            self.profile = global.profile.clone();
+        }
+    }
+
+    /// Whether to print human-readable success messages
+    pub fn show_messages(&self) -> bool {
+        !self.json && !self.quiet
+    }
+
     pub fn format(&self) -> OutputFormat {
-        if self.json {
+        if self.only_ids || self.quiet {
+            OutputFormat::Ids
+        } else if self.json {
             OutputFormat::Json
         } else {
-            OutputFormat::Table
+            self.format.unwrap_or_default()
         }
     }
 }
 
 /// Build config from common arguments
 pub fn build_config(args: &CommonArgs) -> Result<Config> {
-    Config::load(
+    let mut config = Config::load(
+        args.api_key.as_deref(),
+        args.api_key_file.as_deref(),
         args.profile.as_deref(),
         Some(args.format()),
         args.output.as_deref(),
-        args.verbose,
-    )
+        args.verbose && !args.quiet,
+    )?;
+    config.select = args.select.clone();
+    config.fields = args.fields.clone();
+    config.columns = args.columns.clone();
+    config.no_headers = args.no_headers;
+    config.max_width = args.max_width;
+    config.template = args.output_template.clone();
+    config.compact = args.compact;
+    config.output_stderr = args.output_stderr;
+    config.color = args.color;
+    config.timeout = args.timeout.or(config.timeout);
+    config.connect_timeout = args.connect_timeout.or(config.connect_timeout);
+    config.ca_cert = args.ca_cert.clone();
+    config.proxy = args.proxy.clone();
+    config.insecure = args.insecure;
+    config.pool_idle_timeout = args.pool_idle_timeout;
+    config.pool_max_idle = args.pool_max_idle;
+    config.wait = args.wait;
+    config.strict_json = args.strict_json;
+    config.retry = RetryPolicy {
+        max_retries: args.retries,
+        retry_on: args.retry_on.clone(),
+        delay: Duration::from_secs(args.retry_delay),
+        max_delay: Duration::from_secs(args.retry_max_delay),
+    };
+
+    if args.explain_config {
+        eprintln!(
+            "{}",
+            serde_json::to_string_pretty(&explain_config(args, &config))?
+        );
+    }
+
+    Ok(config)
+}
+
+/// Trace of where each effective setting came from, with the key masked
+fn explain_config(args: &CommonArgs, config: &Config) -> serde_json::Value {
+    let setting =
+        |value: serde_json::Value, source: &str| json!({ "value": value, "source": source });
+    let config_file = Config::config_path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let profile_source = if args.profile.is_some() {
+        "--profile flag".to_string()
+    } else if std::env::var("RESEND_PROFILE").is_ok() {
+        "RESEND_PROFILE env".to_string()
+    } else if let Some(name) = Config::load_config_file()
+        .ok()
+        .and_then(|f| f.default_profile)
+    {
+        format!("config file {config_file} (default_profile '{name}')")
+    } else {
+        "default".to_string()
+    };
+
+    let api_key_source = match &config.api_key_source {
+        Some(ApiKeySource::Flag) => "--api-key flag".to_string(),
+        Some(ApiKeySource::File(path)) => format!("file {}", path.display()),
+        Some(ApiKeySource::Env) => "RESEND_API_KEY env".to_string(),
+        Some(ApiKeySource::Profile(name)) => {
+            format!("config file {config_file} (profile '{name}')")
+        }
+        None => "not set".to_string(),
+    };
+
+    let format_source = if args.quiet {
+        "--quiet flag"
+    } else if args.only_ids {
+        "--only-ids flag"
+    } else if args.json {
+        "--json flag"
+    } else if args.format.is_some() {
+        "--format flag"
+    } else {
+        "default"
+    };
+
+    json!({
+        "config_file": config_file,
+        "profile": setting(json!(config.profile), &profile_source),
+        "api_key": setting(json!(config.api_key.as_deref().map(Config::mask_key)), &api_key_source),
+        "format": setting(json!(format!("{:?}", config.format).to_lowercase()), format_source),
+        "output": if config.output_stderr {
+            setting(json!("stderr"), "--output-stderr flag")
+        } else {
+            setting(
+                json!(config.output.as_deref().filter(|p| *p != STDOUT_PATH).unwrap_or("stdout")),
+                if args.output.is_some() { "--output flag" } else { "default" },
+            )
+        },
+        "base_url": setting(
+            json!(config.base_url.as_deref().unwrap_or(BASE_URL)),
+            if std::env::var("RESEND_BASE_URL").is_ok() {
+                "RESEND_BASE_URL env"
+            } else if config.base_url.is_some() {
+                "profile"
+            } else {
+                "built-in"
+            },
+        ),
+        "timeout_secs": setting(
+            json!(config.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS)),
+            if args.timeout.is_some() {
+                "--timeout flag"
+            } else if config.timeout.is_some() {
+                "profile"
+            } else {
+                "default"
+            },
+        ),
+        "connect_timeout_secs": setting(
+            json!(config.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS)),
+            if args.connect_timeout.is_some() {
+                "--connect-timeout flag"
+            } else if config.connect_timeout.is_some() {
+                "profile"
+            } else {
+                "default"
+            },
+        ),
+        "redirect_to": setting(
+            json!(config.redirect_to),
+            if config.redirect_to.is_some() { "profile" } else { "not set" },
+        ),
+    })
+}
+
+/// Build an API client from a validated config
+pub fn build_client(config: &Config) -> Result<ResendClient> {
+    let mut builder = ResendClient::builder(config.api_key.as_deref().unwrap_or_default());
+
+    if let Some(path) = &config.ca_cert {
+        builder = builder.ca_cert(path);
+    }
+
+    if let Some(url) = &config.proxy {
+        builder = builder.proxy(url);
+    }
+
+    if let Some(url) = &config.base_url {
+        builder = builder.base_url(url);
+    }
+
+    if let Some(secs) = config.connect_timeout {
+        builder = builder.connect_timeout(Duration::from_secs(secs));
+    }
+
+    if config.insecure {
+        eprintln!(
+            "Warning: TLS certificate verification is disabled (--insecure). \
+             Your API key can be intercepted by anyone on the network path."
+        );
+        builder = builder.insecure(true);
+    }
+
+    if let Some(secs) = config.pool_idle_timeout {
+        builder = builder.pool_idle_timeout(Duration::from_secs(secs));
+    }
+    if let Some(max) = config.pool_max_idle {
+        builder = builder.pool_max_idle_per_host(max);
+    }
+
+    let mut client = builder
+        .build()?
+        .with_wait(config.wait)
+        .with_strict_json(config.strict_json)
+        .with_retry(config.retry.clone())
+        .with_verbose(config.verbose);
+
+    if let Some(secs) = config.timeout {
+        client = client.with_timeout(Duration::from_secs(secs));
+    }
+
+    Ok(client)
+}
+
+/// Confirmation flags for delete commands
+#[derive(Debug, clap::Args)]
+pub struct DeleteArgs {
+    /// Skip confirmation; production profiles need it twice (--yes --yes)
+    #[arg(long, action = ArgAction::Count)]
+    pub yes: u8,
+
+    /// Delete on a production profile without asking
+    #[arg(long)]
+    pub force_production: bool,
+}
+
+/// Warn on stderr before a command changes data on a production profile
+pub fn production_banner(config: &Config) {
+    if !config.production {
+        return;
+    }
+    let banner = format!(
+        "PRODUCTION: profile '{}' is marked environment: production",
+        config.profile
+    );
+    if std::io::stderr().is_terminal() {
+        eprintln!("\x1b[1;37;41m {banner} \x1b[0m");
+    } else {
+        eprintln!("{banner}");
+    }
+}
+
+/// Ask before deleting `what` on a production profile
+///
+/// Other profiles delete without asking, as before. On production a single
+/// `--yes` is not enough; `--yes --yes` or `--force-production` skips the
+/// prompt, and without a terminal the delete is refused.
+pub fn confirm_production_delete(config: &Config, what: &str, args: &DeleteArgs) -> Result<()> {
+    production_banner(config);
+    if !config.production || args.yes >= 2 || args.force_production {
+        return Ok(());
+    }
+
+    if !std::io::stdin().is_terminal() {
+        bail!(
+            "Refusing to delete {} on production profile '{}' without confirmation. \
+             Pass --yes --yes or --force-production to delete anyway.",
+            what,
+            config.profile
+        );
+    }
+
+    let proceed = Confirm::new()
+        .with_prompt(format!("Delete {} on production?", what))
+        .default(false)
+        .interact()?;
+
+    if !proceed {
+        bail!("Delete cancelled");
+    }
+
+    Ok(())
 }
 
 /// Check config validity and exit if invalid
@@ -58,7 +524,153 @@ pub fn require_valid_config(config: &Config) {
         crate::formatters::output_error(
             "missing_credentials",
             "Missing API key. Run 'resend config setup' or set RESEND_API_KEY.",
+            None,
         );
         std::process::exit(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Args {
+        #[command(flatten)]
+        common: CommonArgs,
+    }
+
+    #[test]
+    fn test_explain_config_reports_sources() {
+        let args = Args::parse_from(["resend", "--json", "--timeout", "5"]).common;
+        let config = Config {
+            api_key: Some("re_1234567890".to_string()),
+            api_key_source: Some(ApiKeySource::Env),
+            timeout: Some(5),
+            format: OutputFormat::Json,
+            ..Default::default()
+        };
+
+        let trace = explain_config(&args, &config);
+        assert_eq!(trace["api_key"]["value"], "re_12345********");
+        assert_eq!(trace["api_key"]["source"], "RESEND_API_KEY env");
+        assert_eq!(trace["format"]["value"], "json");
+        assert_eq!(trace["format"]["source"], "--json flag");
+        assert_eq!(trace["timeout_secs"]["source"], "--timeout flag");
+        assert_eq!(trace["connect_timeout_secs"]["value"], 10);
+        assert_eq!(trace["connect_timeout_secs"]["source"], "default");
+        assert_eq!(trace["output"]["value"], "stdout");
+    }
+
+    #[test]
+    fn test_production_delete_needs_double_yes() {
+        #[derive(Parser)]
+        struct Delete {
+            #[command(flatten)]
+            delete: DeleteArgs,
+        }
+
+        let config = Config {
+            profile: "live".to_string(),
+            production: true,
+            ..Default::default()
+        };
+
+        let twice = Delete::parse_from(["resend", "--yes", "--yes"]).delete;
+        assert!(confirm_production_delete(&config, "domain dom-1", &twice).is_ok());
+
+        let forced = Delete::parse_from(["resend", "--force-production"]).delete;
+        assert!(confirm_production_delete(&config, "domain dom-1", &forced).is_ok());
+
+        let once = Delete::parse_from(["resend", "--yes"]).delete;
+        let staging = Config {
+            production: false,
+            ..config.clone()
+        };
+        assert!(confirm_production_delete(&staging, "domain dom-1", &once).is_ok());
+    }
+
+    #[test]
+    fn test_format_flag() {
+        let args = Args::parse_from(["resend", "--format", "toml"]).common;
+        assert_eq!(args.format(), OutputFormat::Toml);
+
+        let args = Args::parse_from(["resend"]).common;
+        assert_eq!(args.format(), OutputFormat::Table);
+
+        assert!(Args::try_parse_from(["resend", "--json", "--format", "toml"]).is_err());
+    }
+
+    #[test]
+    fn test_quiet_prints_ids_without_messages() {
+        let args = Args::parse_from(["resend", "-q", "--verbose"]).common;
+        assert_eq!(args.format(), OutputFormat::Ids);
+        assert!(!args.show_messages());
+
+        let args = Args::parse_from(["resend"]).common;
+        assert!(args.show_messages());
+
+        assert!(Args::try_parse_from(["resend", "--quiet", "--json"]).is_err());
+    }
+
+    #[test]
+    fn test_max_width_must_be_positive() {
+        assert!(Args::try_parse_from(["resend", "--max-width", "0"]).is_err());
+        let args = Args::parse_from(["resend", "--max-width", "1"]).common;
+        assert_eq!(args.max_width, Some(1));
+    }
+
+    #[test]
+    fn test_inherit_prefers_command_level_values() {
+        let mut common = Args::parse_from(["resend", "--profile", "staging"]).common;
+        let global = GlobalArgs {
+            json: true,
+            format: None,
+            output: Some("out.json".to_string()),
+            profile: Some("production".to_string()),
+            verbose: false,
+        };
+
+        common.inherit(&global);
+
+        assert!(common.json);
+        assert_eq!(common.output.as_deref(), Some("out.json"));
+        assert_eq!(common.profile.as_deref(), Some("staging"));
+    }
+
+    #[test]
+    fn test_inherit_global_format_unless_command_sets_one() {
+        let global = GlobalArgs {
+            format: Some(OutputFormat::Csv),
+            ..Default::default()
+        };
+        let mut common = Args::parse_from(["resend"]).common;
+        common.inherit(&global);
+        assert_eq!(common.format(), OutputFormat::Csv);
+
+        let global = GlobalArgs {
+            json: true,
+            ..Default::default()
+        };
+        let mut common = Args::parse_from(["resend", "--format", "csv"]).common;
+        common.inherit(&global);
+        assert_eq!(common.format(), OutputFormat::Csv);
+        assert!(!common.json);
+
+        let global = GlobalArgs {
+            format: Some(OutputFormat::Csv),
+            json: true,
+            ..Default::default()
+        };
+        for args in [
+            ["resend", "--output-template", "{ID}"],
+            ["resend", "--select", "last_event"],
+        ] {
+            let mut common = Args::parse_from(args).common;
+            common.inherit(&global);
+            assert_eq!(common.format, None, "{args:?}");
+            assert!(!common.json, "{args:?}");
+        }
+    }
+}
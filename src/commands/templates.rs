@@ -1,13 +1,22 @@
 // ABOUTME: Template management commands.
 // ABOUTME: Create, list, get, update, and delete email templates.
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use clap::Subcommand;
-
-use crate::client::ResendClient;
-use crate::commands::{build_config, require_valid_config, CommonArgs};
-use crate::formatters::{format_and_output, format_and_output_single};
-use crate::types::{CreateTemplateRequest, UpdateTemplateRequest};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::commands::{
+    build_client, build_config, confirm_production_delete, production_banner, require_valid_config,
+    CommonArgs, DeleteArgs,
+};
+use crate::formatters::{format_and_output, format_and_output_single, output_result};
+use crate::html_check::check_html;
+use crate::templating::{check_placeholders, extract_variables};
+use crate::types::{
+    CreateTemplateRequest, OutputFormat, Template, TemplateIssue, TemplateVariable,
+    UpdateTemplateRequest,
+};
 
 #[derive(Debug, Subcommand)]
 pub enum TemplatesCommands {
@@ -47,6 +56,29 @@ pub enum TemplatesCommands {
         common: CommonArgs,
     },
 
+    /// List the {{variables}} a template expects
+    Vars {
+        /// Template ID
+        id: String,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Check a template for broken HTML and malformed {{variables}}
+    Validate {
+        /// Template ID to fetch and check
+        #[arg(required_unless_present = "file")]
+        id: Option<String>,
+
+        /// Check a local HTML file instead of a stored template
+        #[arg(long, value_name = "PATH", conflicts_with = "id")]
+        file: Option<PathBuf>,
+
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
     /// Update a template
     Update {
         /// Template ID
@@ -77,12 +109,28 @@ pub enum TemplatesCommands {
         /// Template ID
         id: String,
 
+        #[command(flatten)]
+        delete: DeleteArgs,
+
         #[command(flatten)]
         common: CommonArgs,
     },
 }
 
 impl TemplatesCommands {
+    /// Common arguments of the chosen subcommand
+    pub fn common_mut(&mut self) -> &mut CommonArgs {
+        match self {
+            TemplatesCommands::Create { common, .. }
+            | TemplatesCommands::List { common, .. }
+            | TemplatesCommands::Get { common, .. }
+            | TemplatesCommands::Vars { common, .. }
+            | TemplatesCommands::Validate { common, .. }
+            | TemplatesCommands::Update { common, .. }
+            | TemplatesCommands::Delete { common, .. } => common,
+        }
+    }
+
     pub async fn execute(&self) -> Result<()> {
         match self {
             TemplatesCommands::Create {
@@ -94,8 +142,9 @@ impl TemplatesCommands {
             } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
+                production_banner(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
 
                 let req = CreateTemplateRequest {
                     name: name.clone(),
@@ -106,35 +155,83 @@ impl TemplatesCommands {
 
                 let template = client.create_template(req).await?;
 
-                if common.json {
-                    println!("{}", serde_json::to_string_pretty(&template)?);
-                } else {
-                    println!("Template created successfully!");
-                    println!("ID: {}", template.id);
-                    println!("Name: {}", template.name);
-                }
+                let message = format!(
+                    "Template created successfully!\nID: {}\nName: {}",
+                    template.id, template.name
+                );
 
-                Ok(())
+                output_result(&template, message, &config.output_options())
             }
 
             TemplatesCommands::List { common } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
                 let templates = client.list_templates().await?;
 
-                format_and_output(&templates, config.format, config.output.as_deref())
+                format_and_output(&templates, &config.output_options())
             }
 
             TemplatesCommands::Get { id, common } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
                 let template = client.get_template(id).await?;
 
-                format_and_output_single(&template, config.format, config.output.as_deref())
+                format_and_output_single(&template, &config.output_options())
+            }
+
+            TemplatesCommands::Vars { id, common } => {
+                let config = build_config(common)?;
+                require_valid_config(&config);
+
+                let client = build_client(&config)?;
+                let template = client.get_template(id).await?;
+
+                format_and_output(&template_variables(&template), &config.output_options())
+            }
+
+            TemplatesCommands::Validate { id, file, common } => {
+                let config = build_config(common)?;
+
+                let issues = match (file, id) {
+                    (Some(path), _) => {
+                        let html = fs::read_to_string(path).with_context(|| {
+                            format!("Failed to read template file: {}", path.display())
+                        })?;
+                        template_issues(&[("html", Some(html.as_str()))])
+                    }
+                    (None, Some(id)) => {
+                        require_valid_config(&config);
+                        let client = build_client(&config)?;
+                        let template = client.get_template(id).await?;
+                        template_issues(&[
+                            ("subject", template.subject.as_deref()),
+                            ("html", template.html.as_deref()),
+                            ("text", template.text.as_deref()),
+                        ])
+                    }
+                    (None, None) => unreachable!("clap requires an ID or --file"),
+                };
+
+                if issues.is_empty() {
+                    if config.format == OutputFormat::Table && config.select.is_none() {
+                        println!("No issues found.");
+                        return Ok(());
+                    }
+                } else if config.format == OutputFormat::Table {
+                    eprintln!("{} issue(s) found:", issues.len());
+                }
+
+                format_and_output(&issues, &config.output_options())?;
+
+                if !issues.is_empty() {
+                    bail!("Template has {} issue(s)", issues.len());
+                }
+
+                Ok(())
             }
 
             TemplatesCommands::Update {
@@ -147,8 +244,9 @@ impl TemplatesCommands {
             } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
+                production_banner(&config);
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
 
                 let req = UpdateTemplateRequest {
                     name: name.clone(),
@@ -159,24 +257,20 @@ impl TemplatesCommands {
 
                 let template = client.update_template(id, req).await?;
 
-                if common.json {
-                    println!("{}", serde_json::to_string_pretty(&template)?);
-                } else {
-                    println!("Template updated successfully!");
-                    println!("ID: {}", template.id);
-                }
+                let message = format!("Template updated successfully!\nID: {}", template.id);
 
-                Ok(())
+                output_result(&template, message, &config.output_options())
             }
 
-            TemplatesCommands::Delete { id, common } => {
+            TemplatesCommands::Delete { id, delete, common } => {
                 let config = build_config(common)?;
                 require_valid_config(&config);
+                confirm_production_delete(&config, &format!("template {id}"), delete)?;
 
-                let client = ResendClient::new(config.api_key.as_ref().unwrap())?;
+                let client = build_client(&config)?;
                 client.delete_template(id).await?;
 
-                if !common.json {
+                if common.show_messages() {
                     println!("Template deleted successfully!");
                 }
 
@@ -185,3 +279,97 @@ impl TemplatesCommands {
         }
     }
 }
+
+/// Collect each variable in a template along with the parts using it
+fn template_variables(template: &Template) -> Vec<TemplateVariable> {
+    let parts = [
+        ("subject", template.subject.as_deref()),
+        ("html", template.html.as_deref()),
+        ("text", template.text.as_deref()),
+    ];
+
+    let mut variables: Vec<TemplateVariable> = Vec::new();
+    for (part, source) in parts {
+        for name in extract_variables(source.unwrap_or_default()) {
+            match variables.iter_mut().find(|v| v.name == name) {
+                Some(var) => var.found_in.push(part.to_string()),
+                None => variables.push(TemplateVariable {
+                    name,
+                    found_in: vec![part.to_string()],
+                }),
+            }
+        }
+    }
+
+    variables
+}
+
+/// Lint each template part; HTML structure is only checked in the html part
+fn template_issues(parts: &[(&str, Option<&str>)]) -> Vec<TemplateIssue> {
+    let mut issues = Vec::new();
+
+    for (part, source) in parts {
+        let Some(source) = source else {
+            continue;
+        };
+
+        let mut found = check_placeholders(source);
+        if *part == "html" {
+            found.extend(check_html(source));
+            found.sort_by_key(|(line, _)| *line);
+        }
+
+        issues.extend(found.into_iter().map(|(line, issue)| TemplateIssue {
+            part: part.to_string(),
+            line,
+            issue,
+        }));
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_variables_tracks_locations() {
+        let template = Template {
+            id: "tmpl-1".to_string(),
+            name: "Welcome".to_string(),
+            subject: Some("Welcome to {{company}}".to_string()),
+            html: Some("<h1>Hi {{name}}</h1><p>{{company}}</p>".to_string()),
+            text: None,
+            created_at: None,
+        };
+
+        let vars = template_variables(&template);
+        assert_eq!(vars.len(), 2);
+        assert_eq!(vars[0].name, "company");
+        assert_eq!(vars[0].found_in, vec!["subject", "html"]);
+        assert_eq!(vars[1].name, "name");
+        assert_eq!(vars[1].found_in, vec!["html"]);
+    }
+
+    #[test]
+    fn test_template_issues_labels_parts() {
+        let issues = template_issues(&[
+            ("subject", Some("Hi {{ first name }}")),
+            ("html", Some("<p>{{name}}\n<div>")),
+            ("text", None),
+        ]);
+        let found: Vec<_> = issues
+            .iter()
+            .map(|i| (i.part.as_str(), i.line, i.issue.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("subject", 1, "Invalid variable name '{{first name}}'"),
+                ("html", 1, "Unclosed <p>"),
+                ("html", 2, "Unclosed <div>"),
+            ]
+        );
+    }
+}
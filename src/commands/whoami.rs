@@ -0,0 +1,45 @@
+// ABOUTME: Identity command for the active API key.
+// ABOUTME: Reports the profile, masked key, key source, and inferred permission.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::commands::{build_client, build_config, require_valid_config, CommonArgs};
+use crate::config::Config;
+use crate::formatters::format_and_output_single;
+use crate::types::Tabular;
+
+/// Summary of the key a command would run with
+#[derive(Debug, Serialize, Tabular)]
+struct Identity {
+    profile: String,
+    api_key: String,
+    source: String,
+    permission: String,
+}
+
+/// Show which key is active and what it can do
+pub async fn execute(common: &CommonArgs) -> Result<()> {
+    let config = build_config(common)?;
+    require_valid_config(&config);
+
+    let client = build_client(&config)?;
+    let permission = client.key_permission().await?;
+
+    let identity = Identity {
+        profile: config.profile.clone(),
+        api_key: config
+            .api_key
+            .as_deref()
+            .map(Config::mask_key)
+            .unwrap_or_default(),
+        source: config
+            .api_key_source
+            .as_ref()
+            .map(|s| s.to_string())
+            .unwrap_or_default(),
+        permission: permission.as_str().to_string(),
+    };
+
+    format_and_output_single(&identity, &config.output_options())
+}
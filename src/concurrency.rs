@@ -0,0 +1,66 @@
+// ABOUTME: Bounded concurrent execution for commands that fan out requests.
+// ABOUTME: Runs at most N tasks at once and returns results in input order.
+
+use futures::future::join_all;
+use std::future::Future;
+use tokio::sync::Semaphore;
+
+/// Default number of requests in flight for fan-out commands
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Run `task` for every item with at most `limit` running at once
+///
+/// Results are returned in the same order as `items`, regardless of which
+/// task finishes first.
+pub async fn run_bounded<I, F, Fut>(items: I, limit: usize, task: F) -> Vec<Fut::Output>
+where
+    I: IntoIterator,
+    F: Fn(I::Item) -> Fut,
+    Fut: Future,
+{
+    let semaphore = Semaphore::new(limit.max(1));
+    let semaphore = &semaphore;
+    let task = &task;
+
+    join_all(items.into_iter().map(|item| async move {
+        let _permit = semaphore
+            .acquire()
+            .await
+            .expect("semaphore is never closed");
+        task(item).await
+    }))
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_run_bounded_preserves_order() {
+        let results = run_bounded(vec![30u64, 1, 15], 3, |ms| async move {
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+            ms
+        })
+        .await;
+        assert_eq!(results, vec![30, 1, 15]);
+    }
+
+    #[tokio::test]
+    async fn test_run_bounded_respects_limit() {
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        run_bounded(0..10, 2, |_| async {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            running.fetch_sub(1, Ordering::SeqCst);
+        })
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+}
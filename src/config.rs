@@ -1,14 +1,17 @@
 // ABOUTME: Configuration management for the Resend CLI.
 // ABOUTME: Handles YAML config files, profiles, and environment variables.
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-use crate::types::OutputFormat;
+use crate::client::RetryPolicy;
+use crate::formatters::OutputOptions;
+use crate::types::{ColorChoice, OutputFormat};
 
 const DEFAULT_PROFILE: &str = "default";
 
@@ -16,6 +19,53 @@ const DEFAULT_PROFILE: &str = "default";
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Profile {
     pub api_key: Option<String>,
+    /// File holding the API key, e.g. a mounted secret; used when `api_key` is unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_file: Option<PathBuf>,
+    /// Send every email to this address instead of the real recipients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_to: Option<String>,
+    /// Environment label; `production` turns on the destructive-command guard
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+    /// API root to use instead of the public endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Seconds allowed for a whole request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Seconds allowed to establish a connection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+}
+
+/// Environment label that marks a profile as production
+pub const PRODUCTION: &str = "production";
+
+impl Profile {
+    /// Whether this profile is marked `environment: production`
+    pub fn is_production(&self) -> bool {
+        self.environment
+            .as_deref()
+            .is_some_and(|env| env.eq_ignore_ascii_case(PRODUCTION))
+    }
+
+    /// Reject timeouts of zero, which would fail every request
+    pub fn check_timeouts(&self, name: &str) -> Result<()> {
+        for (key, value) in [
+            ("timeout", self.timeout),
+            ("connect_timeout", self.connect_timeout),
+        ] {
+            if value == Some(0) {
+                bail!(
+                    "Profile '{}' has {}: 0; it must be a positive number of seconds",
+                    name,
+                    key
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Configuration file structure
@@ -23,26 +73,141 @@ pub struct Profile {
 pub struct ConfigFile {
     #[serde(default)]
     pub profiles: HashMap<String, Profile>,
+    /// Profile used when neither `--profile` nor `RESEND_PROFILE` is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+}
+
+impl ConfigFile {
+    /// The configured default profile, or "default"
+    pub fn default_profile(&self) -> &str {
+        self.default_profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// Make an existing profile the default
+    pub fn set_default_profile(&mut self, name: &str) -> Result<()> {
+        if !self.profiles.contains_key(name) {
+            bail!("Profile '{}' not found", name);
+        }
+        self.default_profile = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Remove a profile, failing if it isn't configured
+    pub fn remove_profile(&mut self, name: &str) -> Result<Profile> {
+        let profile = self
+            .profiles
+            .remove(name)
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", name))?;
+        if self.default_profile.as_deref() == Some(name) {
+            self.default_profile = None;
+        }
+        Ok(profile)
+    }
+
+    /// Move a profile to a new name, keeping its settings
+    pub fn rename_profile(&mut self, old: &str, new: &str) -> Result<()> {
+        if self.profiles.contains_key(new) {
+            bail!("Profile '{}' already exists", new);
+        }
+        let was_default = self.default_profile.as_deref() == Some(old);
+        let profile = self.remove_profile(old)?;
+        self.profiles.insert(new.to_string(), profile);
+        if was_default {
+            self.default_profile = Some(new.to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Where the resolved API key came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiKeySource {
+    /// The `--api-key` flag
+    Flag,
+    /// A key file, from `--api-key-file` or a profile's `api_key_file`
+    File(PathBuf),
+    /// The `RESEND_API_KEY` environment variable
+    Env,
+    /// A profile in the config file
+    Profile(String),
+}
+
+impl std::fmt::Display for ApiKeySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiKeySource::Flag => write!(f, "--api-key flag"),
+            ApiKeySource::File(path) => write!(f, "file {}", path.display()),
+            ApiKeySource::Env => write!(f, "env (RESEND_API_KEY)"),
+            ApiKeySource::Profile(name) => write!(f, "profile '{name}'"),
+        }
+    }
 }
 
 /// Runtime configuration with resolved values
 #[derive(Debug, Clone)]
 pub struct Config {
     pub api_key: Option<String>,
+    pub api_key_source: Option<ApiKeySource>,
     pub profile: String,
     pub format: OutputFormat,
     pub output: Option<String>,
+    pub output_stderr: bool,
     pub verbose: bool,
+    pub timeout: Option<u64>,
+    pub connect_timeout: Option<u64>,
+    pub ca_cert: Option<PathBuf>,
+    pub proxy: Option<String>,
+    pub insecure: bool,
+    pub pool_idle_timeout: Option<u64>,
+    pub pool_max_idle: Option<usize>,
+    pub wait: bool,
+    pub strict_json: bool,
+    pub redirect_to: Option<String>,
+    pub production: bool,
+    pub select: Option<String>,
+    pub fields: Option<Vec<String>>,
+    pub columns: Option<Vec<String>>,
+    pub no_headers: bool,
+    pub template: Option<String>,
+    pub max_width: Option<usize>,
+    pub compact: bool,
+    pub color: ColorChoice,
+    pub retry: RetryPolicy,
+    pub base_url: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             api_key: None,
+            api_key_source: None,
             profile: DEFAULT_PROFILE.to_string(),
             format: OutputFormat::Table,
             output: None,
+            output_stderr: false,
             verbose: false,
+            timeout: None,
+            connect_timeout: None,
+            ca_cert: None,
+            proxy: None,
+            insecure: false,
+            pool_idle_timeout: None,
+            pool_max_idle: None,
+            wait: false,
+            strict_json: false,
+            redirect_to: None,
+            production: false,
+            select: None,
+            fields: None,
+            columns: None,
+            no_headers: false,
+            template: None,
+            max_width: None,
+            compact: false,
+            color: ColorChoice::Auto,
+            retry: RetryPolicy::default(),
+            base_url: None,
         }
     }
 }
@@ -88,49 +253,111 @@ impl Config {
         let contents =
             serde_yaml::to_string(config_file).with_context(|| "Failed to serialize config")?;
 
-        fs::write(&path, contents)
-            .with_context(|| format!("Failed to write config file: {path:?}"))?;
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = fs::metadata(&path)?.permissions();
-            perms.set_mode(0o600);
-            fs::set_permissions(&path, perms)?;
-        }
+        write_private(&path, &contents)
+            .with_context(|| format!("Failed to write config file: {path:?}"))
+    }
 
-        Ok(())
+    /// The profile used when none is given: `RESEND_PROFILE`, the config
+    /// file's `default_profile`, or "default"
+    pub fn default_profile_name(config_file: &ConfigFile) -> String {
+        std::env::var("RESEND_PROFILE")
+            .unwrap_or_else(|_| config_file.default_profile().to_string())
     }
 
-    /// Load configuration with priority: env vars > config file > defaults
+    /// Load configuration with priority: flags > env vars > config file > defaults
     pub fn load(
+        api_key: Option<&str>,
+        api_key_file: Option<&Path>,
         profile: Option<&str>,
         format: Option<OutputFormat>,
         output: Option<&str>,
         verbose: bool,
     ) -> Result<Self> {
+        let config_file = Self::load_config_file().unwrap_or_default();
         let profile_name = profile
             .map(|s| s.to_string())
-            .or_else(|| std::env::var("RESEND_PROFILE").ok())
-            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
-
-        let config_file = Self::load_config_file().unwrap_or_default();
+            .unwrap_or_else(|| Self::default_profile_name(&config_file));
         let file_profile = config_file.profiles.get(&profile_name);
+        if let Some(p) = file_profile {
+            p.check_timeouts(&profile_name)?;
+        }
 
-        // Resolve API key: env > config file
-        let resolved_api_key = std::env::var("RESEND_API_KEY")
-            .ok()
-            .or_else(|| file_profile.and_then(|p| p.api_key.clone()));
+        // Resolve API key: flag > key file flag > env > profile key > profile key file
+        let (resolved_api_key, api_key_source) = if let Some(key) = api_key {
+            (Some(key.to_string()), Some(ApiKeySource::Flag))
+        } else if let Some(path) = api_key_file {
+            (
+                Some(read_key_file(path)?),
+                Some(ApiKeySource::File(path.to_path_buf())),
+            )
+        } else if let Ok(key) = std::env::var("RESEND_API_KEY") {
+            (Some(key), Some(ApiKeySource::Env))
+        } else if let Some(key) = file_profile.and_then(|p| p.api_key.clone()) {
+            (Some(key), Some(ApiKeySource::Profile(profile_name.clone())))
+        } else if let Some(path) = file_profile.and_then(|p| p.api_key_file.as_deref()) {
+            (
+                Some(read_key_file(path)?),
+                Some(ApiKeySource::File(path.to_path_buf())),
+            )
+        } else {
+            (None, None)
+        };
 
         Ok(Self {
             api_key: resolved_api_key,
+            api_key_source,
             profile: profile_name,
             format: format.unwrap_or(OutputFormat::Table),
             output: output.map(|s| s.to_string()),
+            output_stderr: false,
             verbose,
+            timeout: file_profile.and_then(|p| p.timeout),
+            connect_timeout: file_profile.and_then(|p| p.connect_timeout),
+            ca_cert: None,
+            proxy: None,
+            insecure: false,
+            pool_idle_timeout: None,
+            pool_max_idle: None,
+            wait: false,
+            strict_json: false,
+            redirect_to: file_profile.and_then(|p| p.redirect_to.clone()),
+            production: file_profile.is_some_and(Profile::is_production),
+            select: None,
+            fields: None,
+            columns: None,
+            no_headers: false,
+            template: None,
+            max_width: None,
+            compact: false,
+            color: ColorChoice::Auto,
+            retry: RetryPolicy::default(),
+            base_url: std::env::var("RESEND_BASE_URL")
+                .ok()
+                .or_else(|| file_profile.and_then(|p| p.base_url.clone())),
         })
     }
 
+    /// Output settings for the formatters
+    pub fn output_options(&self) -> OutputOptions {
+        OutputOptions {
+            format: self.format,
+            path: self.output.clone(),
+            stderr: self.output_stderr,
+            select: self.select.clone(),
+            fields: self.fields.clone(),
+            columns: self.columns.clone(),
+            no_headers: self.no_headers,
+            template: self.template.clone(),
+            max_width: self.max_width,
+            compact: self.compact,
+            color: crate::formatters::use_color(
+                self.color,
+                self.output.as_deref(),
+                self.output_stderr,
+            ),
+        }
+    }
+
     /// Check if configuration has required credentials
     pub fn is_valid(&self) -> bool {
         self.api_key.is_some()
@@ -140,13 +367,33 @@ impl Config {
     pub fn set_profile(profile_name: &str, api_key: &str) -> Result<()> {
         let mut config_file = Self::load_config_file().unwrap_or_default();
 
-        config_file.profiles.insert(
-            profile_name.to_string(),
-            Profile {
-                api_key: Some(api_key.to_string()),
-            },
-        );
+        config_file
+            .profiles
+            .entry(profile_name.to_string())
+            .or_default()
+            .api_key = Some(api_key.to_string());
+
+        Self::save_config_file(&config_file)
+    }
 
+    /// Delete a profile from the config file
+    pub fn delete_profile(profile_name: &str) -> Result<()> {
+        let mut config_file = Self::load_config_file()?;
+        config_file.remove_profile(profile_name)?;
+        Self::save_config_file(&config_file)
+    }
+
+    /// Rename a profile in the config file
+    pub fn rename_profile(old: &str, new: &str) -> Result<()> {
+        let mut config_file = Self::load_config_file()?;
+        config_file.rename_profile(old, new)?;
+        Self::save_config_file(&config_file)
+    }
+
+    /// Set the default profile in the config file
+    pub fn set_default_profile(profile_name: &str) -> Result<()> {
+        let mut config_file = Self::load_config_file()?;
+        config_file.set_default_profile(profile_name)?;
         Self::save_config_file(&config_file)
     }
 
@@ -168,6 +415,81 @@ impl Config {
     }
 }
 
+/// Write a file readable only by the current user (0600 on Unix)
+///
+/// New files are created with the restricted mode so the contents are never
+/// briefly world-readable; existing files are tightened as well.
+pub fn write_private(path: &Path, contents: &str) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+/// The file's permission bits if it grants more than owner read/write
+///
+/// Always `None` on platforms without Unix permissions.
+pub fn loose_mode(path: &Path) -> Result<Option<u32>> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path)?.permissions().mode() & 0o777;
+        if mode & !0o600 != 0 {
+            return Ok(Some(mode));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(None)
+}
+
+/// Reset a file to owner read/write only (0600 on Unix)
+pub fn restrict_permissions(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Read an API key from a file, ignoring surrounding whitespace
+fn read_key_file(path: &Path) -> Result<String> {
+    let path = &expand_home(path, dirs::home_dir());
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read API key file: {}", path.display()))?;
+    let key = contents.trim();
+    if key.is_empty() {
+        bail!("API key file {} is empty", path.display());
+    }
+    Ok(key.to_string())
+}
+
+/// Expand a leading `~` to the home directory, as a shell would for the flag
+fn expand_home(path: &Path, home: Option<PathBuf>) -> PathBuf {
+    match (path.strip_prefix("~"), home) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -207,18 +529,211 @@ mod tests {
         assert_eq!(Config::mask_key("re_123456789"), "re_12345********");
     }
 
+    #[test]
+    fn test_api_key_source_display() {
+        assert_eq!(ApiKeySource::Flag.to_string(), "--api-key flag");
+        assert_eq!(ApiKeySource::Env.to_string(), "env (RESEND_API_KEY)");
+        assert_eq!(
+            ApiKeySource::Profile("prod".to_string()).to_string(),
+            "profile 'prod'"
+        );
+    }
+
+    #[test]
+    fn test_api_key_flag_takes_precedence() {
+        let config = Config::load(Some("re_from_flag"), None, None, None, None, false).unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("re_from_flag"));
+        assert_eq!(config.api_key_source, Some(ApiKeySource::Flag));
+    }
+
+    #[test]
+    fn test_api_key_file_is_read_and_trimmed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key");
+        fs::write(&path, "re_from_file\n").unwrap();
+
+        let config = Config::load(None, Some(&path), None, None, None, false).unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("re_from_file"));
+        assert_eq!(
+            config.api_key_source,
+            Some(ApiKeySource::File(path.clone()))
+        );
+
+        fs::write(&path, "  \n").unwrap();
+        let err = Config::load(None, Some(&path), None, None, None, false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("API key file {} is empty", path.display())
+        );
+
+        let missing = dir.path().join("missing");
+        let err = Config::load(None, Some(&missing), None, None, None, false).unwrap_err();
+        assert!(err.to_string().starts_with("Failed to read API key file"));
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = Some(PathBuf::from("/home/me"));
+        assert_eq!(
+            expand_home(Path::new("~/.secrets/key"), home.clone()),
+            PathBuf::from("/home/me/.secrets/key")
+        );
+        assert_eq!(
+            expand_home(Path::new("/run/key"), home.clone()),
+            PathBuf::from("/run/key")
+        );
+        assert_eq!(
+            expand_home(Path::new("~other/key"), home),
+            PathBuf::from("~other/key")
+        );
+        assert_eq!(
+            expand_home(Path::new("~/key"), None),
+            PathBuf::from("~/key")
+        );
+    }
+
     #[test]
     fn test_config_file_default() {
         let config_file = ConfigFile::default();
         assert!(config_file.profiles.is_empty());
     }
 
+    #[test]
+    fn test_remove_profile() {
+        let mut config_file = ConfigFile::default();
+        config_file
+            .profiles
+            .insert("staging".to_string(), Profile::default());
+
+        assert!(config_file.remove_profile("staging").is_ok());
+        assert!(config_file.profiles.is_empty());
+
+        let err = config_file.remove_profile("staging").unwrap_err();
+        assert_eq!(err.to_string(), "Profile 'staging' not found");
+    }
+
+    #[test]
+    fn test_rename_profile_keeps_settings() {
+        let mut config_file = ConfigFile::default();
+        config_file.profiles.insert(
+            "staging".to_string(),
+            Profile {
+                api_key: Some("re_staging".to_string()),
+                ..Default::default()
+            },
+        );
+        config_file
+            .profiles
+            .insert("prod".to_string(), Profile::default());
+
+        let err = config_file.rename_profile("staging", "prod").unwrap_err();
+        assert_eq!(err.to_string(), "Profile 'prod' already exists");
+        let err = config_file.rename_profile("qa", "test").unwrap_err();
+        assert_eq!(err.to_string(), "Profile 'qa' not found");
+
+        config_file.rename_profile("staging", "test").unwrap();
+        assert!(!config_file.profiles.contains_key("staging"));
+        assert_eq!(
+            config_file.profiles["test"].api_key.as_deref(),
+            Some("re_staging")
+        );
+    }
+
+    #[test]
+    fn test_default_profile_follows_rename_and_delete() {
+        let mut config_file = ConfigFile::default();
+        assert_eq!(config_file.default_profile(), "default");
+
+        let err = config_file.set_default_profile("prod").unwrap_err();
+        assert_eq!(err.to_string(), "Profile 'prod' not found");
+
+        config_file
+            .profiles
+            .insert("prod".to_string(), Profile::default());
+        config_file.set_default_profile("prod").unwrap();
+        assert_eq!(config_file.default_profile(), "prod");
+
+        config_file.rename_profile("prod", "live").unwrap();
+        assert_eq!(config_file.default_profile(), "live");
+
+        config_file.remove_profile("live").unwrap();
+        assert_eq!(config_file.default_profile(), "default");
+    }
+
+    #[test]
+    fn test_config_file_default_profile_round_trip() {
+        let config_file: ConfigFile =
+            serde_yaml::from_str("default_profile: prod\nprofiles:\n  prod: {}\n").unwrap();
+        assert_eq!(config_file.default_profile(), "prod");
+
+        let yaml = serde_yaml::to_string(&ConfigFile::default()).unwrap();
+        assert!(!yaml.contains("default_profile"));
+    }
+
     #[test]
     fn test_profile_serialize() {
         let profile = Profile {
             api_key: Some("re_test".to_string()),
+            ..Default::default()
         };
         let yaml = serde_yaml::to_string(&profile).unwrap();
         assert!(yaml.contains("api_key: re_test"));
+        assert!(!yaml.contains("redirect_to"));
+        assert!(!yaml.contains("environment"));
+    }
+
+    #[test]
+    fn test_profile_check_timeouts() {
+        let profile: Profile = serde_yaml::from_str("timeout: 120\nconnect_timeout: 20\n").unwrap();
+        assert!(profile.check_timeouts("slow").is_ok());
+
+        let profile: Profile = serde_yaml::from_str("connect_timeout: 0\n").unwrap();
+        assert_eq!(
+            profile.check_timeouts("slow").unwrap_err().to_string(),
+            "Profile 'slow' has connect_timeout: 0; it must be a positive number of seconds"
+        );
+    }
+
+    #[test]
+    fn test_profile_is_production() {
+        let profile: Profile =
+            serde_yaml::from_str("api_key: re_live\nenvironment: Production\n").unwrap();
+        assert!(profile.is_production());
+
+        let profile: Profile = serde_yaml::from_str("environment: staging\n").unwrap();
+        assert!(!profile.is_production());
+        assert!(!Profile::default().is_production());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private_sets_owner_only_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret");
+        fs::write(&path, "old contents that are longer").unwrap();
+
+        write_private(&path, "re_token").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "re_token");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_loose_mode_detected_and_restricted() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yml");
+        fs::write(&path, "profiles: {}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        assert_eq!(loose_mode(&path).unwrap(), Some(0o644));
+
+        restrict_permissions(&path).unwrap();
+        assert_eq!(loose_mode(&path).unwrap(), None);
     }
 }
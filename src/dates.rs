@@ -0,0 +1,267 @@
+// ABOUTME: Date and time parsing helpers.
+// ABOUTME: Normalises API timestamps and resolves relative times like "in 2 hours".
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{
+    DateTime, Days, Duration, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, TimeZone, Utc,
+};
+
+/// Parse a timestamp returned by the API
+///
+/// Accepts RFC 3339 as well as the Postgres-style `2024-08-05 11:52:01.858+00`
+/// form that some endpoints return.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    if let Ok(dt) = DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%#z") {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+/// Resolve a `--scheduled-at` value to a timestamp the API accepts
+///
+/// Timestamps with an offset are passed through unchanged. ISO 8601 without
+/// one (`2025-01-20T09:00`, or a bare date meaning midnight) is read in
+/// `now`'s time zone, like the forms below. Relative forms (`+30m`,
+/// `in 2 hours`) and day/time forms (`tomorrow 9am`, `today at 17:30`) are
+/// resolved against `now`, in its time zone, to RFC 3339 UTC. Either way the
+/// time must be in the future.
+pub fn resolve_schedule<Tz: TimeZone>(value: &str, now: DateTime<Tz>) -> Result<String> {
+    let value = value.trim();
+
+    if let Some(at) = parse_timestamp(value) {
+        ensure_future(value, at, &now)?;
+        return Ok(value.to_string());
+    }
+    if let Some(at) = local_iso_8601(value, &now) {
+        ensure_future(value, at, &now)?;
+        return Ok(at.to_rfc3339_opts(SecondsFormat::Secs, true));
+    }
+
+    let lower = value.to_lowercase();
+    let at = relative_time(&lower, &now)
+        .or_else(|| day_and_time(&lower, &now))
+        .ok_or_else(|| {
+            anyhow!(
+                "Could not understand scheduled time '{}'; use ISO 8601 or forms like \
+                 'in 2 hours', '+30m', or 'tomorrow 9am'",
+                value
+            )
+        })?;
+    ensure_future(value, at, &now)?;
+    Ok(at.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+/// ISO 8601 date or date-time without an offset, in `now`'s time zone
+fn local_iso_8601<Tz: TimeZone>(value: &str, now: &DateTime<Tz>) -> Option<DateTime<Utc>> {
+    let naive = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .map(|date| date.and_time(NaiveTime::MIN))
+        })?;
+    now.timezone()
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+fn ensure_future<Tz: TimeZone>(value: &str, at: DateTime<Utc>, now: &DateTime<Tz>) -> Result<()> {
+    if at <= *now {
+        bail!("Scheduled time '{}' is in the past", value);
+    }
+    Ok(())
+}
+
+/// `+30m` or `in 2 hours`
+fn relative_time<Tz: TimeZone>(value: &str, now: &DateTime<Tz>) -> Option<DateTime<Utc>> {
+    let amount = value
+        .strip_prefix('+')
+        .or_else(|| value.strip_prefix("in "))?
+        .trim();
+    let digits = amount.find(|c: char| !c.is_ascii_digit())?;
+    let count: i64 = amount[..digits].parse().ok()?;
+    let unit_secs = match amount[digits..].trim() {
+        "s" | "sec" | "secs" | "second" | "seconds" => 1,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 60 * 60,
+        "d" | "day" | "days" => 24 * 60 * 60,
+        "w" | "week" | "weeks" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    let offset = Duration::try_seconds(count.checked_mul(unit_secs)?)?;
+    now.with_timezone(&Utc).checked_add_signed(offset)
+}
+
+/// `tomorrow`, `tomorrow 9am`, `today at 17:30`, or just `5pm`
+fn day_and_time<Tz: TimeZone>(value: &str, now: &DateTime<Tz>) -> Option<DateTime<Utc>> {
+    let (days, rest) = if let Some(rest) = value.strip_prefix("tomorrow") {
+        (1, rest)
+    } else if let Some(rest) = value.strip_prefix("today") {
+        (0, rest)
+    } else {
+        (0, value)
+    };
+    let rest = rest.trim();
+    let rest = rest.strip_prefix("at ").unwrap_or(rest).trim();
+
+    let time = match rest {
+        "" if days > 0 => now.time(),
+        "" => return None,
+        clock => parse_clock(clock)?,
+    };
+    let date = now.date_naive().checked_add_days(Days::new(days))?;
+    now.timezone()
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// `9am`, `9:30 pm`, or 24-hour `17:30`
+fn parse_clock(value: &str) -> Option<NaiveTime> {
+    let (clock, pm) = if let Some(clock) = value.strip_suffix("am") {
+        (clock.trim(), Some(false))
+    } else if let Some(clock) = value.strip_suffix("pm") {
+        (clock.trim(), Some(true))
+    } else {
+        (value, None)
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        None if pm.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match pm {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(pm) => hour % 12 + if pm { 12 } else { 0 },
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    #[test]
+    fn test_parse_rfc3339() {
+        let dt = parse_timestamp("2025-01-20T09:00:00Z").unwrap();
+        assert_eq!(dt, Utc.with_ymd_and_hms(2025, 1, 20, 9, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_parse_postgres_style() {
+        let dt = parse_timestamp("2025-01-20 09:00:00.123+00").unwrap();
+        assert_eq!(dt.timestamp(), 1737363600);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse_timestamp("next tuesday").is_none());
+    }
+
+    fn now() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2025-01-15T10:00:00+02:00").unwrap()
+    }
+
+    #[test]
+    fn test_resolve_schedule_relative() {
+        for (value, expected) in [
+            ("+30m", "2025-01-15T08:30:00Z"),
+            ("in 2 hours", "2025-01-15T10:00:00Z"),
+            ("In 1 day", "2025-01-16T08:00:00Z"),
+            ("+1w", "2025-01-22T08:00:00Z"),
+        ] {
+            assert_eq!(
+                resolve_schedule(value, now()).unwrap(),
+                expected,
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_schedule_day_and_time() {
+        for (value, expected) in [
+            ("tomorrow 9am", "2025-01-16T07:00:00Z"),
+            ("tomorrow at 9:30 pm", "2025-01-16T19:30:00Z"),
+            ("tomorrow", "2025-01-16T08:00:00Z"),
+            ("today 17:45", "2025-01-15T15:45:00Z"),
+            ("12pm", "2025-01-15T10:00:00Z"),
+        ] {
+            assert_eq!(
+                resolve_schedule(value, now()).unwrap(),
+                expected,
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_schedule_passes_timestamps_through() {
+        assert_eq!(
+            resolve_schedule("2025-01-20T09:00:00+01:00", now()).unwrap(),
+            "2025-01-20T09:00:00+01:00"
+        );
+    }
+
+    #[test]
+    fn test_resolve_schedule_reads_iso_8601_without_offset_as_local() {
+        for (value, expected) in [
+            ("2025-01-20T09:00:00", "2025-01-20T07:00:00Z"),
+            ("2025-01-20T09:00", "2025-01-20T07:00:00Z"),
+            ("2025-01-20", "2025-01-19T22:00:00Z"),
+        ] {
+            assert_eq!(
+                resolve_schedule(value, now()).unwrap(),
+                expected,
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_schedule_rejects_past_and_malformed_dates() {
+        let err = resolve_schedule("2020-01-01", now()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Scheduled time '2020-01-01' is in the past"
+        );
+        for value in ["2024-13-99xyz", "2025-01-20xyz", "2025-02-30"] {
+            let err = resolve_schedule(value, now()).unwrap_err();
+            assert!(
+                err.to_string()
+                    .starts_with("Could not understand scheduled time"),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_schedule_rejects_past_and_unknown() {
+        let err = resolve_schedule("today 9am", now()).unwrap_err();
+        assert_eq!(err.to_string(), "Scheduled time 'today 9am' is in the past");
+        assert!(resolve_schedule("2025-01-01T00:00:00Z", now()).is_err());
+        assert!(resolve_schedule("next tuesday", now())
+            .unwrap_err()
+            .to_string()
+            .starts_with("Could not understand scheduled time 'next tuesday'"));
+        assert!(resolve_schedule("13pm", now()).is_err());
+        assert!(resolve_schedule("in 5 fortnights", now()).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+// ABOUTME: Local duplicate-send protection.
+// ABOUTME: Records hashes of sent requests in the cache dir with timestamps.
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Entries younger than this are kept even when the current window is shorter
+const MIN_RETENTION_SECS: i64 = 24 * 60 * 60;
+
+/// Hash a request in a stable, field-order-independent way
+pub fn request_hash<T: Serialize>(req: &T) -> Result<String> {
+    // Round-trip through Value so object keys are sorted
+    let normalized = serde_json::to_string(&serde_json::to_value(req)?)?;
+    let digest = Sha256::digest(normalized.as_bytes());
+    Ok(digest.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Hashes of recent sends and when they happened (unix seconds)
+#[derive(Debug)]
+pub struct DedupStore {
+    path: PathBuf,
+    entries: HashMap<String, i64>,
+}
+
+impl DedupStore {
+    /// Default store location under the user's cache dir
+    pub fn default_path() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "resend").map(|dirs| dirs.cache_dir().join("sent.json"))
+    }
+
+    /// Load the store, treating a missing or corrupt file as empty
+    pub fn load(path: &Path) -> Self {
+        let entries = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        Self {
+            path: path.to_path_buf(),
+            entries,
+        }
+    }
+
+    /// Whether the same request was sent within `window_secs` of `now`
+    pub fn is_duplicate(&self, hash: &str, window_secs: u64, now: i64) -> bool {
+        self.entries
+            .get(hash)
+            .is_some_and(|sent| now - sent < window_secs as i64)
+    }
+
+    /// Record a send and drop entries older than the retention period
+    pub fn record(&mut self, hash: &str, window_secs: u64, now: i64) {
+        let retention = (window_secs as i64).max(MIN_RETENTION_SECS);
+        self.entries.retain(|_, sent| now - *sent < retention);
+        self.entries.insert(hash.to_string(), now);
+    }
+
+    /// Write the store back to disk
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create cache directory: {parent:?}"))?;
+        }
+
+        let contents = serde_json::to_string(&self.entries)?;
+        fs::write(&self.path, contents)
+            .with_context(|| format!("Failed to write dedup store: {:?}", self.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_hash_ignores_key_order() {
+        let a = json!({"from": "a@example.com", "to": ["b@example.com"]});
+        let b = json!({"to": ["b@example.com"], "from": "a@example.com"});
+        assert_eq!(request_hash(&a).unwrap(), request_hash(&b).unwrap());
+
+        let c = json!({"to": ["c@example.com"], "from": "a@example.com"});
+        assert_ne!(request_hash(&a).unwrap(), request_hash(&c).unwrap());
+    }
+
+    #[test]
+    fn test_duplicate_within_window_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sent.json");
+
+        let mut store = DedupStore::load(&path);
+        assert!(!store.is_duplicate("abc", 60, 1_000));
+        store.record("abc", 60, 1_000);
+        store.save().unwrap();
+
+        let store = DedupStore::load(&path);
+        assert!(store.is_duplicate("abc", 60, 1_030));
+        assert!(!store.is_duplicate("abc", 60, 1_060));
+    }
+
+    #[test]
+    fn test_record_prunes_old_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = DedupStore::load(&dir.path().join("sent.json"));
+
+        store.record("old", 60, 0);
+        store.record("new", 60, MIN_RETENTION_SECS + 1);
+        assert!(!store.entries.contains_key("old"));
+        assert!(store.entries.contains_key("new"));
+    }
+}
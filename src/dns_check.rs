@@ -0,0 +1,273 @@
+// ABOUTME: Live DNS checks for a domain's verification records.
+// ABOUTME: Resolves each record with the system resolver and compares it with the expected value.
+
+use std::time::Duration;
+
+use hickory_resolver::error::ResolveErrorKind;
+use hickory_resolver::proto::rr::RecordType;
+use hickory_resolver::system_conf::read_system_conf;
+use hickory_resolver::TokioAsyncResolver;
+use serde::Serialize;
+
+use crate::types::{Cell, DnsRecord, Domain, Tabular};
+
+/// Outcome of comparing one record with what DNS currently serves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckResult {
+    Match,
+    Mismatch,
+    /// The lookup itself failed, so nothing is known about the record
+    Unknown,
+}
+
+impl CheckResult {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CheckResult::Match => "match",
+            CheckResult::Mismatch => "mismatch",
+            CheckResult::Unknown => "unknown",
+        }
+    }
+}
+
+impl Cell for CheckResult {
+    fn cell(&self) -> Option<String> {
+        Some(self.as_str().to_string())
+    }
+}
+
+/// A domain record alongside the values observed in DNS
+#[derive(Debug, Serialize, Tabular)]
+pub struct RecordCheck {
+    pub record: String,
+    pub name: String,
+    pub r#type: String,
+    pub expected: String,
+    pub observed: Vec<String>,
+    pub result: CheckResult,
+}
+
+/// A resolver using the system's DNS configuration with the given timeout
+///
+/// Returns `None` when the system configuration can't be read, in which case
+/// every record is reported as unknown.
+pub fn system_resolver(timeout: Duration) -> Option<TokioAsyncResolver> {
+    let (config, mut options) = read_system_conf().ok()?;
+    options.timeout = timeout;
+    Some(TokioAsyncResolver::tokio(config, options))
+}
+
+/// Resolve every record on the domain and compare it with the expected value
+///
+/// Lookups fail soft: a record whose lookup errors is marked unknown rather
+/// than aborting the whole check.
+pub async fn check_records(
+    domain: &Domain,
+    resolver: Option<&TokioAsyncResolver>,
+) -> Vec<RecordCheck> {
+    let mut checks = Vec::new();
+    for record in domain.records.iter().flatten() {
+        let record_type = record.r#type.clone().unwrap_or_else(|| "TXT".to_string());
+        let fqdn = qualified_name(&record.name, &domain.name);
+        let observed = match resolver {
+            Some(resolver) => lookup(resolver, &fqdn, &record_type).await,
+            None => None,
+        };
+        checks.push(compare(record, &record_type, fqdn, observed));
+    }
+    checks
+}
+
+/// Build a check from a record and the lookup outcome (`None` if it failed)
+fn compare(
+    record: &DnsRecord,
+    record_type: &str,
+    fqdn: String,
+    observed: Option<Vec<String>>,
+) -> RecordCheck {
+    let result = match &observed {
+        None => CheckResult::Unknown,
+        Some(values) => {
+            let expected = expected_value(record, record_type);
+            if values.iter().any(|v| normalize(v, record_type) == expected) {
+                CheckResult::Match
+            } else {
+                CheckResult::Mismatch
+            }
+        }
+    };
+
+    RecordCheck {
+        record: record.record.clone(),
+        name: fqdn,
+        r#type: record_type.to_string(),
+        expected: record.value.clone(),
+        observed: observed.unwrap_or_default(),
+        result,
+    }
+}
+
+/// Query the resolver, returning `None` when no answer could be obtained
+///
+/// A name that does not exist, or has no records of the type, is a valid
+/// answer with no values.
+async fn lookup(
+    resolver: &TokioAsyncResolver,
+    name: &str,
+    record_type: &str,
+) -> Option<Vec<String>> {
+    let record_type: RecordType = record_type.to_ascii_uppercase().parse().ok()?;
+    // A trailing dot stops the system search domains being appended
+    match resolver.lookup(format!("{name}."), record_type).await {
+        Ok(answer) => Some(answer.iter().map(|data| data.to_string()).collect()),
+        Err(err) => match err.kind() {
+            ResolveErrorKind::NoRecordsFound { .. } => Some(Vec::new()),
+            _ => None,
+        },
+    }
+}
+
+/// Expand a record name relative to the domain into a fully-qualified name
+fn qualified_name(name: &str, domain: &str) -> String {
+    let name = name.trim_end_matches('.');
+    if name.is_empty() || name == "@" || name == domain {
+        domain.to_string()
+    } else if name.ends_with(&format!(".{domain}")) {
+        name.to_string()
+    } else {
+        format!("{name}.{domain}")
+    }
+}
+
+/// Expected value in the same normalized form as observed answers
+fn expected_value(record: &DnsRecord, record_type: &str) -> String {
+    let value = normalize(&record.value, record_type);
+    match (record_type.eq_ignore_ascii_case("MX"), record.priority) {
+        (true, Some(priority)) if !value.contains(' ') => format!("{priority} {value}"),
+        _ => value,
+    }
+}
+
+/// Normalize a record value for comparison
+///
+/// TXT answers arrive quoted and possibly split into several strings; host
+/// names are case-insensitive and may carry a trailing dot.
+fn normalize(value: &str, record_type: &str) -> String {
+    if record_type.eq_ignore_ascii_case("TXT") {
+        let trimmed = value.trim();
+        if trimmed.starts_with('"') {
+            trimmed
+                .split('"')
+                .skip(1)
+                .step_by(2)
+                .collect::<Vec<_>>()
+                .concat()
+        } else {
+            trimmed.to_string()
+        }
+    } else {
+        value
+            .split_whitespace()
+            .map(|part| part.trim_end_matches('.').to_ascii_lowercase())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(record_type: &str, name: &str, value: &str, priority: Option<i32>) -> DnsRecord {
+        DnsRecord {
+            record: "SPF".to_string(),
+            name: name.to_string(),
+            r#type: Some(record_type.to_string()),
+            ttl: None,
+            value: value.to_string(),
+            status: None,
+            priority,
+        }
+    }
+
+    #[test]
+    fn test_qualified_name() {
+        assert_eq!(qualified_name("send", "example.com"), "send.example.com");
+        assert_eq!(qualified_name("@", "example.com"), "example.com");
+        assert_eq!(
+            qualified_name("send.example.com.", "example.com"),
+            "send.example.com"
+        );
+    }
+
+    #[test]
+    fn test_compare_txt_joins_quoted_strings() {
+        let expected = record("TXT", "send", "v=spf1 include:amazonses.com ~all", None);
+        let observed = vec![r#""v=spf1 include:amazon" "ses.com ~all""#.to_string()];
+        let check = compare(&expected, "TXT", "send.example.com".into(), Some(observed));
+        assert_eq!(check.result, CheckResult::Match);
+    }
+
+    #[test]
+    fn test_compare_mx_includes_priority() {
+        let expected = record(
+            "MX",
+            "send",
+            "feedback-smtp.us-east-1.amazonses.com",
+            Some(10),
+        );
+
+        let good = vec!["10 Feedback-SMTP.us-east-1.amazonses.com.".to_string()];
+        let check = compare(&expected, "MX", "send.example.com".into(), Some(good));
+        assert_eq!(check.result, CheckResult::Match);
+
+        let wrong = vec!["20 feedback-smtp.us-east-1.amazonses.com.".to_string()];
+        let check = compare(&expected, "MX", "send.example.com".into(), Some(wrong));
+        assert_eq!(check.result, CheckResult::Mismatch);
+    }
+
+    #[test]
+    fn test_compare_failed_lookup_is_unknown() {
+        let expected = record("CNAME", "resend._domainkey", "x.dkim.amazonses.com", None);
+        let check = compare(
+            &expected,
+            "CNAME",
+            "resend._domainkey.example.com".into(),
+            None,
+        );
+        assert_eq!(check.result, CheckResult::Unknown);
+        assert_eq!(check.row().last().unwrap(), "unknown");
+    }
+
+    #[tokio::test]
+    async fn test_check_records_unreachable_resolver_is_unknown() {
+        use hickory_resolver::config::{NameServerConfig, Protocol, ResolverConfig, ResolverOpts};
+
+        // Nothing listens on the discard port, so every lookup fails
+        let mut config = ResolverConfig::new();
+        config.add_name_server(NameServerConfig::new(
+            "127.0.0.1:9".parse().unwrap(),
+            Protocol::Udp,
+        ));
+        let mut options = ResolverOpts::default();
+        options.timeout = Duration::from_millis(200);
+        options.attempts = 0;
+        let resolver = TokioAsyncResolver::tokio(config, options);
+
+        let domain = Domain {
+            id: "dom-1".to_string(),
+            name: "example.com".to_string(),
+            status: None,
+            region: None,
+            records: Some(vec![record("TXT", "send", "v=spf1 -all", None)]),
+        };
+
+        let checks = check_records(&domain, Some(&resolver)).await;
+        assert_eq!(checks[0].result, CheckResult::Unknown);
+        assert_eq!(checks[0].name, "send.example.com");
+
+        let checks = check_records(&domain, None).await;
+        assert_eq!(checks[0].result, CheckResult::Unknown);
+    }
+}
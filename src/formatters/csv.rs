@@ -0,0 +1,65 @@
+// ABOUTME: CSV formatting for list output.
+// ABOUTME: Used with --format csv; a header row (unless --no-headers), then one row per item.
+
+use anyhow::Result;
+
+use super::table::{column_indices, pick};
+use super::OutputOptions;
+use crate::types::Tabular;
+
+/// Format items as CSV with the same columns as the table
+pub fn format_csv<T: Tabular>(items: &[T], options: &OutputOptions) -> Result<String> {
+    let headers = T::headers();
+    let indices = column_indices(&headers, options.columns.as_deref())?;
+
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    if !options.no_headers {
+        writer.write_record(pick(&headers, &indices))?;
+    }
+    for item in items {
+        writer.write_record(pick(&item.row(), &indices))?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8(bytes)?.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Contact;
+
+    #[test]
+    fn test_format_csv_quotes_and_selects_columns() {
+        let contacts: Vec<Contact> = serde_json::from_value(serde_json::json!([
+            {"id": "c-1", "email": "ada@example.com", "first_name": "Ada, Countess", "unsubscribed": false},
+            {"id": "c-2", "email": "bob@example.com", "unsubscribed": true}
+        ]))
+        .unwrap();
+
+        let all = format_csv(&contacts, &OutputOptions::default()).unwrap();
+        assert_eq!(
+            all.lines().next().unwrap(),
+            "ID,EMAIL,FIRST NAME,LAST NAME,UNSUBSCRIBED,CREATED"
+        );
+        assert!(all.contains("\"Ada, Countess\""));
+
+        let options = OutputOptions {
+            columns: Some(vec!["email".to_string(), "unsubscribed".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_csv(&contacts, &options).unwrap(),
+            "EMAIL,UNSUBSCRIBED\nada@example.com,false\nbob@example.com,true"
+        );
+
+        let options = OutputOptions {
+            no_headers: true,
+            ..options
+        };
+        assert_eq!(
+            format_csv(&contacts, &options).unwrap(),
+            "ada@example.com,false\nbob@example.com,true"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+// ABOUTME: Human-friendly and JSON rendering of command errors.
+// ABOUTME: Maps API error variants to explanations, suggestions, and error codes.
+
+use crate::client::{request_id, ApiError};
+use crate::formatters::output_error;
+use crate::types::ErrorResponse;
+
+/// Report an error on stderr in the JSON error envelope
+///
+/// The envelope is the same one `output_error` uses: an error code, the
+/// message, and the API request ID when there is one.
+pub fn output_json_error(err: &anyhow::Error) {
+    output_error(error_code(err), &error_message(err), request_id(err));
+}
+
+/// Stable machine-readable class of an error
+fn error_code(err: &anyhow::Error) -> &'static str {
+    match err.downcast_ref::<ApiError>() {
+        Some(ApiError::AuthenticationError) => "authentication_error",
+        Some(ApiError::NotFoundError(_)) => "not_found",
+        Some(ApiError::RateLimitError(_)) => "rate_limit_exceeded",
+        Some(ApiError::ValidationError(_)) => "validation_error",
+        Some(ApiError::ApiError { .. }) => "api_error",
+        Some(ApiError::NetworkError(_)) => "network_error",
+        None => "error",
+    }
+}
+
+/// The error's message without the request ID, which has its own field
+fn error_message(err: &anyhow::Error) -> String {
+    match err.downcast_ref::<ApiError>() {
+        Some(api_error) => api_error.to_string(),
+        None => format!("{err:#}"),
+    }
+}
+
+/// Render an error as a short explanation plus a suggested next step
+pub fn render_error(err: &anyhow::Error) -> String {
+    let Some(api_error) = err.downcast_ref::<ApiError>() else {
+        return format!("Error: {err:#}");
+    };
+
+    let explanation = explain(api_error);
+    match request_id(err) {
+        Some(id) => format!("{explanation}\n  request-id: {id} (quote this to Resend support)"),
+        None => explanation,
+    }
+}
+
+fn explain(api_error: &ApiError) -> String {
+    match api_error {
+        ApiError::AuthenticationError => [
+            "Authentication failed.",
+            "  The API key was rejected or lacks permission for this action.",
+            "  Check which key is active with 'resend whoami', or run 'resend config setup'.",
+        ]
+        .join("\n"),
+        ApiError::NotFoundError(body) => format!(
+            "Not found: {}\n  Check the ID is correct and belongs to this account.",
+            api_message(body)
+        ),
+        ApiError::RateLimitError(Some(secs)) => {
+            format!("Rate limited — try again in {secs} seconds.")
+        }
+        ApiError::RateLimitError(None) => {
+            "Rate limited — wait a moment and try again.".to_string()
+        }
+        ApiError::ValidationError(body) => format!(
+            "Validation failed — check: {}\n  Run the command with --help to see the expected arguments.",
+            api_message(body)
+        ),
+        ApiError::ApiError { status, message } => format!(
+            "Resend returned an unexpected error (HTTP {status}): {}\n  If this keeps happening, check https://resend-status.com.",
+            api_message(message)
+        ),
+        ApiError::NetworkError(message) => format!(
+            "Could not reach the Resend API: {message}\n  Check your connection and proxy settings, or raise --timeout."
+        ),
+    }
+}
+
+/// Pull the `message` out of a JSON error body, falling back to the raw text
+fn api_message(body: &str) -> String {
+    serde_json::from_str::<ErrorResponse>(body)
+        .ok()
+        .and_then(|e| e.message)
+        .unwrap_or_else(|| body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_rate_limit_with_reset() {
+        let err = anyhow::Error::from(ApiError::RateLimitError(Some(12)));
+        assert_eq!(
+            render_error(&err),
+            "Rate limited — try again in 12 seconds."
+        );
+    }
+
+    #[test]
+    fn test_render_validation_uses_message_field() {
+        let body = r#"{"name": "validation_error", "message": "Invalid `to` field."}"#;
+        let err = anyhow::Error::from(ApiError::ValidationError(body.to_string()));
+        let rendered = render_error(&err);
+        assert!(rendered.starts_with("Validation failed — check: Invalid `to` field."));
+        assert!(!rendered.contains("validation_error"));
+    }
+
+    #[test]
+    fn test_render_appends_request_id() {
+        let err = anyhow::Error::new(crate::client::RequestId("req_1".to_string()))
+            .context(ApiError::RateLimitError(None));
+        assert_eq!(
+            render_error(&err),
+            "Rate limited — wait a moment and try again.\n  request-id: req_1 (quote this to Resend support)"
+        );
+    }
+
+    #[test]
+    fn test_json_error_code_and_message() {
+        let err = anyhow::Error::new(crate::client::RequestId("req_1".to_string()))
+            .context(ApiError::NotFoundError("dom-1".to_string()));
+        assert_eq!(error_code(&err), "not_found");
+        assert_eq!(error_message(&err), "Resource not found: dom-1");
+
+        let err = anyhow::anyhow!("disk full").context("Failed to write output");
+        assert_eq!(error_code(&err), "error");
+        assert_eq!(error_message(&err), "Failed to write output: disk full");
+    }
+
+    #[test]
+    fn test_render_other_errors_with_context_chain() {
+        let err = anyhow::anyhow!("disk full").context("Failed to write output");
+        assert_eq!(
+            render_error(&err),
+            "Error: Failed to write output: disk full"
+        );
+    }
+}
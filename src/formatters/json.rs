@@ -1,17 +1,25 @@
 // ABOUTME: JSON formatting for structured output.
-// ABOUTME: Used when --json flag is provided.
+// ABOUTME: Used with --json, and with --format ndjson for one object per line.
 
 use anyhow::Result;
 use serde::Serialize;
 
-/// Format a list of items as JSON
-pub fn format_json<T: Serialize>(items: &[T]) -> Result<String> {
-    Ok(serde_json::to_string_pretty(items)?)
+/// Format data as JSON, pretty-printed unless `compact` is set
+pub fn format_json<T: Serialize + ?Sized>(data: &T, compact: bool) -> Result<String> {
+    Ok(if compact {
+        serde_json::to_string(data)?
+    } else {
+        serde_json::to_string_pretty(data)?
+    })
 }
 
-/// Format a single item as JSON
-pub fn format_json_single<T: Serialize>(item: &T) -> Result<String> {
-    Ok(serde_json::to_string_pretty(item)?)
+/// Format items as newline-delimited JSON, one compact object per line
+pub fn format_ndjson<T: Serialize>(items: &[T]) -> Result<String> {
+    Ok(items
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<serde_json::Result<Vec<_>>>()?
+        .join("\n"))
 }
 
 #[cfg(test)]
@@ -22,22 +30,37 @@ mod tests {
     #[test]
     fn test_format_json_empty() {
         let emails: Vec<Email> = vec![];
-        let output = format_json(&emails).unwrap();
+        let output = format_json(&emails, false).unwrap();
         assert_eq!(output, "[]");
     }
 
     #[test]
     fn test_format_json_with_items() {
         let emails = vec![Email {
-            id: "email-123".to_string(),
             from: Some("from@example.com".to_string()),
             to: Some(vec!["to@example.com".to_string()]),
             subject: Some("Test".to_string()),
-            created_at: None,
-            last_event: None,
+            ..Email::fixture("email-123")
         }];
-        let output = format_json(&emails).unwrap();
+        let output = format_json(&emails, false).unwrap();
         assert!(output.contains("email-123"));
         assert!(output.contains("from@example.com"));
     }
+
+    #[test]
+    fn test_format_ndjson() {
+        let items = serde_json::json!([{"id": "a", "tags": [1, 2]}, {"id": "b"}]);
+        let items = items.as_array().unwrap();
+        assert_eq!(
+            format_ndjson(items).unwrap(),
+            "{\"id\":\"a\",\"tags\":[1,2]}\n{\"id\":\"b\"}"
+        );
+        assert_eq!(format_ndjson::<Email>(&[]).unwrap(), "");
+    }
+
+    #[test]
+    fn test_format_json_compact() {
+        let output = format_json(&serde_json::json!({"id": "email-123"}), true).unwrap();
+        assert_eq!(output, r#"{"id":"email-123"}"#);
+    }
 }
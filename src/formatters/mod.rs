@@ -1,47 +1,228 @@
 // ABOUTME: Output formatting for CLI results.
-// ABOUTME: Supports table and JSON output formats.
+// ABOUTME: Supports table, JSON, TOML, and ID-only output formats.
 
+pub mod csv;
+pub mod errors;
 pub mod json;
+pub mod select;
 pub mod table;
+pub mod template;
+pub mod toml;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use serde::Serialize;
+use serde_json::Value;
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 
-use crate::types::{OutputFormat, Tabular};
+use crate::types::{ColorChoice, OutputFormat, Tabular};
+
+/// How and where command output is rendered
+#[derive(Debug, Clone, Default)]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    pub path: Option<String>,
+    pub stderr: bool,
+    pub select: Option<String>,
+    pub fields: Option<Vec<String>>,
+    /// Table and CSV columns to show, by header name
+    pub columns: Option<Vec<String>>,
+    /// Leave out the table header and separator, and the CSV header row
+    pub no_headers: bool,
+    /// Render each item with this `{COLUMN}` template instead of a table
+    pub template: Option<String>,
+    /// Truncate table cells to this many display columns
+    pub max_width: Option<usize>,
+    pub compact: bool,
+    /// Color table headers and status values with ANSI codes
+    pub color: bool,
+}
+
+impl OutputOptions {
+    /// Write formatted output to the chosen file, stdout, or stderr
+    fn write(&self, content: &str) -> Result<()> {
+        if self.stderr {
+            eprintln!("{content}");
+            return Ok(());
+        }
+        write_output(content, self.path.as_deref())
+    }
+
+    /// Render data as JSON, applying --select or --fields to each item
+    fn json<T: Serialize + ?Sized>(&self, data: &T) -> Result<String> {
+        json::format_json(&self.shaped(data)?, self.compact)
+    }
+
+    /// Render a list as NDJSON, or anything else as a single compact line
+    fn ndjson<T: Serialize + ?Sized>(&self, data: &T) -> Result<String> {
+        match self.shaped(data)? {
+            Value::Array(items) => json::format_ndjson(&items),
+            item => json::format_json(&item, true),
+        }
+    }
+
+    fn shaped<T: Serialize + ?Sized>(&self, data: &T) -> Result<Value> {
+        Ok(match serde_json::to_value(data)? {
+            Value::Array(items) => Value::Array(items.iter().map(|i| self.shape(i)).collect()),
+            item => self.shape(&item),
+        })
+    }
+
+    fn shape(&self, item: &Value) -> Value {
+        match (&self.select, &self.fields) {
+            (Some(path), _) => select::select_path(item, path)
+                .cloned()
+                .unwrap_or(Value::Null),
+            (None, Some(fields)) => select::project(item, fields),
+            (None, None) => item.clone(),
+        }
+    }
+}
 
 /// Format and output data based on format setting
 pub fn format_and_output<T: Serialize + Tabular>(
     data: &[T],
-    format: OutputFormat,
-    output_path: Option<&str>,
+    options: &OutputOptions,
+) -> Result<()> {
+    options.write(&format_list(data, options)?)
+}
+
+/// Format and output data partitioned by the value of one table column
+///
+/// Groups appear in order of first appearance, each under a titled
+/// sub-table; JSON output is an object of group value to items.
+pub fn format_and_output_grouped<T: Serialize + Tabular>(
+    data: &[T],
+    column: &str,
+    options: &OutputOptions,
 ) -> Result<()> {
-    let formatted = match format {
-        OutputFormat::Table => table::format_table(data),
-        OutputFormat::Json => json::format_json(data)?,
+    let headers = T::headers();
+    let Some(index) = headers.iter().position(|h| h.eq_ignore_ascii_case(column)) else {
+        bail!(
+            "Cannot group by '{}'; choose one of: {}",
+            column,
+            headers.join(", ").to_lowercase()
+        );
+    };
+
+    let mut groups: Vec<(String, Vec<&T>)> = Vec::new();
+    for item in data {
+        let key = item.row().swap_remove(index);
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, items)) => items.push(item),
+            None => groups.push((key, vec![item])),
+        }
+    }
+
+    let formatted = if matches!(
+        options.format,
+        OutputFormat::Json | OutputFormat::Ndjson | OutputFormat::Toml
+    ) {
+        let mut object = serde_json::Map::new();
+        for (key, items) in &groups {
+            object.insert(key.clone(), options.shaped(items)?);
+        }
+        match options.format {
+            OutputFormat::Toml => toml::format_toml_single(&Value::Object(object))?,
+            OutputFormat::Ndjson => json::format_json(&object, true)?,
+            _ => json::format_json(&object, options.compact)?,
+        }
+    } else {
+        groups
+            .iter()
+            .map(|(key, items)| {
+                let label = if key.is_empty() { "(none)" } else { key };
+                let title = format!("{}: {} ({})", headers[index], label, items.len());
+                Ok(format!("{}\n{}", title, format_list(items, options)?))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .join("\n\n")
     };
 
-    write_output(&formatted, output_path)
+    options.write(&formatted)
+}
+
+/// Render a list in the requested format
+fn format_list<T: Serialize + Tabular>(data: &[T], options: &OutputOptions) -> Result<String> {
+    Ok(match (&options.select, options.format) {
+        (_, OutputFormat::Json) => options.json(data)?,
+        (_, OutputFormat::Ndjson) => options.ndjson(data)?,
+        (_, OutputFormat::Toml) => toml::format_toml_list(&options.shaped(data)?)?,
+        (Some(path), format) => data
+            .iter()
+            .map(|item| selected_line(item, path, format))
+            .collect::<Result<Vec<_>>>()?
+            .join("\n"),
+        (None, OutputFormat::Table) => match &options.template {
+            Some(template) => template::format_template(data, template)?,
+            None => table::format_table(data, options)?,
+        },
+        (None, OutputFormat::Csv) => csv::format_csv(data, options)?,
+        (None, OutputFormat::Ids) => table::format_ids(data),
+    })
 }
 
 /// Format and output a single item
 pub fn format_and_output_single<T: Serialize + Tabular>(
     data: &T,
-    format: OutputFormat,
-    output_path: Option<&str>,
+    options: &OutputOptions,
+) -> Result<()> {
+    let formatted = match (&options.select, options.format) {
+        (_, OutputFormat::Json) => options.json(data)?,
+        (_, OutputFormat::Ndjson) => options.ndjson(data)?,
+        (_, OutputFormat::Toml) => toml::format_toml_single(&options.shaped(data)?)?,
+        (Some(path), format) => selected_line(data, path, format)?,
+        (None, OutputFormat::Table) => match &options.template {
+            Some(template) => template::format_template(std::slice::from_ref(data), template)?,
+            None => table::format_single(data, options)?,
+        },
+        (None, OutputFormat::Csv) => csv::format_csv(std::slice::from_ref(data), options)?,
+        (None, OutputFormat::Ids) => data.id_value().unwrap_or_default(),
+    };
+
+    options.write(&formatted)
+}
+
+/// Output the result of a create/update/send action
+///
+/// JSON and --select render the API response; otherwise the human-readable
+/// `message` is printed.
+pub fn output_result<T: Serialize>(
+    data: &T,
+    message: String,
+    options: &OutputOptions,
 ) -> Result<()> {
-    let formatted = match format {
-        OutputFormat::Table => table::format_single(data),
-        OutputFormat::Json => json::format_json_single(data)?,
+    let formatted = match (&options.select, options.format) {
+        (_, OutputFormat::Json) => options.json(data)?,
+        (_, OutputFormat::Ndjson) => options.ndjson(data)?,
+        (_, OutputFormat::Toml) => toml::format_toml_single(&options.shaped(data)?)?,
+        (Some(path), _) => select::select_text(data, path)?,
+        (None, OutputFormat::Ids) => select::select_text(data, "id")?,
+        (None, OutputFormat::Table | OutputFormat::Csv) => message,
     };
 
-    write_output(&formatted, output_path)
+    options.write(&formatted)
+}
+
+/// Render a selected field, prefixed by the item's ID in ID-only mode
+fn selected_line<T: Serialize + Tabular>(
+    item: &T,
+    path: &str,
+    format: OutputFormat,
+) -> Result<String> {
+    let value = select::select_text(item, path)?;
+    Ok(match format {
+        OutputFormat::Ids => format!("{}\t{}", item.id_value().unwrap_or_default(), value),
+        _ => value,
+    })
 }
 
-/// Write output to file or stdout
-fn write_output(content: &str, output_path: Option<&str>) -> Result<()> {
-    match output_path {
+/// Path that means "write to stdout" when passed to `--output`
+pub const STDOUT_PATH: &str = "-";
+
+/// Write output to a file, or to stdout when no path (or `-`) is given
+pub fn write_output(content: &str, output_path: Option<&str>) -> Result<()> {
+    match output_file(output_path) {
         Some(path) => {
             fs::write(path, content)?;
             Ok(())
@@ -54,11 +235,121 @@ fn write_output(content: &str, output_path: Option<&str>) -> Result<()> {
     }
 }
 
-/// Output an error to stderr as JSON
-pub fn output_error(error: &str, message: &str) {
-    let error_json = serde_json::json!({
+/// Whether table output should be colored
+///
+/// `always` and `never` are taken at their word. `auto` colors only output
+/// headed for a terminal, and never when `NO_COLOR` is set to anything.
+pub fn use_color(choice: ColorChoice, output_path: Option<&str>, stderr: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+            let terminal = if stderr {
+                io::stderr().is_terminal()
+            } else {
+                output_file(output_path).is_none() && io::stdout().is_terminal()
+            };
+            !no_color && terminal
+        }
+    }
+}
+
+/// The file to write to, if any; `-` is treated as stdout
+fn output_file(output_path: Option<&str>) -> Option<&str> {
+    output_path.filter(|path| *path != STDOUT_PATH)
+}
+
+/// Output an error to stderr as JSON, with the API request ID when known
+pub fn output_error(error: &str, message: &str, request_id: Option<&str>) {
+    let mut error_json = serde_json::json!({
         "error": error,
         "message": message
     });
+    if let Some(id) = request_id {
+        error_json["request_id"] = id.into();
+    }
     eprintln!("{}", serde_json::to_string(&error_json).unwrap_or_default());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_output_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.json");
+        write_output("{\"id\": \"email-123\"}", path.to_str()).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "{\"id\": \"email-123\"}");
+    }
+
+    #[test]
+    fn test_use_color_choices() {
+        assert!(use_color(ColorChoice::Always, Some("out.txt"), false));
+        assert!(!use_color(ColorChoice::Never, None, false));
+        assert!(!use_color(ColorChoice::Auto, Some("out.txt"), false));
+    }
+
+    #[test]
+    fn test_dash_means_stdout() {
+        assert_eq!(output_file(Some(STDOUT_PATH)), None);
+        assert_eq!(output_file(None), None);
+        assert_eq!(output_file(Some("out.json")), Some("out.json"));
+        assert_eq!(output_file(Some("./-")), Some("./-"));
+    }
+
+    #[test]
+    fn test_json_applies_fields_to_each_item() {
+        let options = OutputOptions {
+            format: OutputFormat::Json,
+            fields: Some(vec!["id".to_string()]),
+            compact: true,
+            ..Default::default()
+        };
+        let data = serde_json::json!([{"id": "a", "name": "x"}, {"id": "b", "name": "y"}]);
+        assert_eq!(options.json(&data).unwrap(), r#"[{"id":"a"},{"id":"b"}]"#);
+    }
+
+    #[test]
+    fn test_ndjson_puts_each_item_on_its_own_line() {
+        let options = OutputOptions {
+            format: OutputFormat::Ndjson,
+            fields: Some(vec!["id".to_string()]),
+            ..Default::default()
+        };
+        let data = serde_json::json!([{"id": "a", "name": "x"}, {"id": "b", "name": "y"}]);
+        assert_eq!(
+            options.ndjson(&data).unwrap(),
+            "{\"id\":\"a\"}\n{\"id\":\"b\"}"
+        );
+        assert_eq!(
+            options.ndjson(&serde_json::json!({"id": "a"})).unwrap(),
+            r#"{"id":"a"}"#
+        );
+    }
+
+    #[test]
+    fn test_grouped_rejects_unknown_column() {
+        let err = format_and_output_grouped(crate::types::REGIONS, "size", &Default::default())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot group by 'size'; choose one of: region, location"
+        );
+    }
+
+    #[test]
+    fn test_json_select_wins_over_fields() {
+        let options = OutputOptions {
+            format: OutputFormat::Json,
+            select: Some("name".to_string()),
+            fields: Some(vec!["id".to_string()]),
+            compact: true,
+            ..Default::default()
+        };
+        let data = serde_json::json!({"id": "a", "name": "x"});
+        assert_eq!(options.json(&data).unwrap(), r#""x""#);
+    }
+}
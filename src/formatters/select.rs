@@ -0,0 +1,95 @@
+// ABOUTME: Dotted-path field extraction for --select.
+// ABOUTME: Pulls one field out of each item's JSON representation.
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Look up a dotted path such as `records.0.value` in a JSON value
+///
+/// Numeric segments index into arrays.
+pub fn select_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(value, |current, segment| match current {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+}
+
+/// Select a field from an item and render it as raw text
+///
+/// Strings print without quotes, missing fields as an empty line, and
+/// anything else as compact JSON.
+pub fn select_text<T: Serialize>(item: &T, path: &str) -> Result<String> {
+    let value = serde_json::to_value(item)?;
+    Ok(match select_path(&value, path) {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(items)) if items.iter().all(Value::is_string) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(", "),
+        Some(other) => other.to_string(),
+    })
+}
+
+/// Keep only the given dotted field paths of an object, keyed by path
+///
+/// Missing fields come out as null so every object has the same shape.
+pub fn project(value: &Value, fields: &[String]) -> Value {
+    Value::Object(
+        fields
+            .iter()
+            .map(|path| {
+                let field = select_path(value, path).cloned().unwrap_or(Value::Null);
+                (path.clone(), field)
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_select_path_nested_and_indexed() {
+        let value = json!({"records": [{"value": "v=spf1"}], "status": "verified"});
+        assert_eq!(select_path(&value, "status"), Some(&json!("verified")));
+        assert_eq!(
+            select_path(&value, "records.0.value"),
+            Some(&json!("v=spf1"))
+        );
+        assert_eq!(select_path(&value, "records.1.value"), None);
+        assert_eq!(select_path(&value, "status.missing"), None);
+    }
+
+    #[test]
+    fn test_select_text_formats_values() {
+        let item = json!({"to": ["a@example.com", "b@example.com"], "n": 3, "x": null});
+        assert_eq!(
+            select_text(&item, "to").unwrap(),
+            "a@example.com, b@example.com"
+        );
+        assert_eq!(select_text(&item, "n").unwrap(), "3");
+        assert_eq!(select_text(&item, "x").unwrap(), "");
+        assert_eq!(select_text(&item, "missing").unwrap(), "");
+    }
+
+    #[test]
+    fn test_project_keeps_requested_fields() {
+        let value = json!({"id": "d-1", "name": "example.com", "records": [{"value": "v=spf1"}]});
+        let fields = vec![
+            "id".to_string(),
+            "records.0.value".to_string(),
+            "nope".to_string(),
+        ];
+        assert_eq!(
+            project(&value, &fields),
+            json!({"id": "d-1", "records.0.value": "v=spf1", "nope": null})
+        );
+    }
+}
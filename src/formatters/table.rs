@@ -1,50 +1,197 @@
 // ABOUTME: Table formatting using the tabled crate.
 // ABOUTME: Renders data as human-readable tables.
 
+use anyhow::{anyhow, Result};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::OutputOptions;
 use crate::types::Tabular;
 
-/// Format a list of items as a table
-pub fn format_table<T: Tabular>(items: &[T]) -> String {
-    if items.is_empty() {
-        return "No results found.".to_string();
+const BOLD: &str = "\x1b[1m";
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// Columns whose values are colored by what they say
+const STATUS_COLUMNS: &[&str] = &["STATUS", "EVENT"];
+
+/// Color for a status value: green for success, red for failure, yellow
+/// for in-progress
+fn status_color(value: &str) -> Option<&'static str> {
+    match value.to_ascii_lowercase().as_str() {
+        "delivered" | "verified" => Some(GREEN),
+        "bounced" | "failed" | "complained" => Some(RED),
+        "pending" | "delivery_delayed" => Some(YELLOW),
+        _ => None,
+    }
+}
+
+/// Wrap text in an ANSI color code
+fn paint(text: &str, code: &str) -> String {
+    format!("{code}{text}{RESET}")
+}
+
+/// Positions of the headers named in `columns`, in the order given
+///
+/// Names match case-insensitively; without `columns`, every column is kept.
+pub fn column_indices(headers: &[&str], columns: Option<&[String]>) -> Result<Vec<usize>> {
+    let Some(columns) = columns else {
+        return Ok((0..headers.len()).collect());
+    };
+    columns
+        .iter()
+        .map(|name| {
+            headers
+                .iter()
+                .position(|h| h.eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Unknown column '{}'; valid columns: {}",
+                        name,
+                        headers.join(", ")
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Keep only the cells at `indices`
+pub fn pick<S: ToString>(row: &[S], indices: &[usize]) -> Vec<String> {
+    indices.iter().map(|&i| row[i].to_string()).collect()
+}
+
+/// Shorten text to at most `max` display columns, ending in an ellipsis
+///
+/// Cuts between characters, so multibyte characters are never split.
+pub fn truncate(text: &str, max: usize) -> String {
+    if text.width() <= max {
+        return text.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let mut out = String::new();
+    let mut width = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if width + w + 1 > max {
+            break;
+        }
+        out.push(c);
+        width += w;
+    }
+    out.push('…');
+    out
+}
+
+/// Apply `--max-width` to a row of cells
+fn fit(row: Vec<String>, max_width: Option<usize>) -> Vec<String> {
+    match max_width {
+        Some(max) => row.iter().map(|cell| truncate(cell, max)).collect(),
+        None => row,
     }
+}
 
+/// Format a list of items as a table, with the chosen columns and colors
+pub fn format_table<T: Tabular>(items: &[T], options: &OutputOptions) -> Result<String> {
     let headers = T::headers();
-    let mut rows: Vec<Vec<String>> = vec![headers.iter().map(|s| s.to_string()).collect()];
+    let indices = column_indices(&headers, options.columns.as_deref())?;
+
+    if items.is_empty() {
+        let message = if options.no_headers {
+            ""
+        } else {
+            "No results found."
+        };
+        return Ok(message.to_string());
+    }
+
+    let mut rows: Vec<Vec<String>> = vec![pick(&headers, &indices)];
 
     for item in items {
-        rows.push(item.row());
+        rows.push(pick(&item.row(), &indices));
     }
 
-    format_rows(&rows)
+    Ok(format_rows(
+        &rows,
+        !options.no_headers,
+        options.color,
+        options.max_width,
+    ))
 }
 
-/// Format a single item as key-value pairs
-pub fn format_single<T: Tabular>(item: &T) -> String {
+/// Format a single item as key-value pairs, with the chosen columns and colors
+pub fn format_single<T: Tabular>(item: &T, options: &OutputOptions) -> Result<String> {
     let headers = T::headers();
-    let values = item.row();
+    let indices = column_indices(&headers, options.columns.as_deref())?;
+    let headers = pick(&headers, &indices);
+    let full_values = pick(&item.row(), &indices);
+    let values = fit(full_values.clone(), options.max_width);
 
     let mut output = String::new();
-    for (header, value) in headers.iter().zip(values.iter()) {
-        output.push_str(&format!("{}: {}\n", header, value));
+    for ((header, value), full_value) in headers.iter().zip(values.iter()).zip(&full_values) {
+        if options.no_headers {
+            output.push_str(&format!("{}\n", value));
+        } else if options.color {
+            // Color from the full value, which truncation may have cut short
+            let value = match status_color(full_value)
+                .filter(|_| STATUS_COLUMNS.contains(&header.as_str()))
+            {
+                Some(code) => paint(value, code),
+                None => value.clone(),
+            };
+            output.push_str(&format!("{}: {}\n", paint(header, BOLD), value));
+        } else {
+            output.push_str(&format!("{}: {}\n", header, value));
+        }
     }
-    output
+    Ok(output)
+}
+
+/// Format the ID column of each item, one per line
+pub fn format_ids<T: Tabular>(items: &[T]) -> String {
+    items
+        .iter()
+        .filter_map(|item| item.id_value())
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 /// Format rows into a table string
-fn format_rows(rows: &[Vec<String>]) -> String {
-    if rows.is_empty() {
+///
+/// The first row holds the headers; without `headers` it and its separator
+/// are left out. With `color`, the header row is bold and status columns
+/// are colored; codes wrap each padded cell so they don't affect alignment.
+/// Status columns and colors are judged before `max_width` truncates cells.
+fn format_rows(
+    full_rows: &[Vec<String>],
+    headers: bool,
+    color: bool,
+    max_width: Option<usize>,
+) -> String {
+    if full_rows.is_empty() {
         return String::new();
     }
 
-    // Calculate column widths
+    let status_columns: Vec<bool> = full_rows[0]
+        .iter()
+        .map(|h| STATUS_COLUMNS.contains(&h.as_str()))
+        .collect();
+    let rows: Vec<Vec<String>> = full_rows
+        .iter()
+        .map(|row| fit(row.clone(), max_width))
+        .collect();
+
+    // Calculate column widths as displayed, so wide and combining
+    // characters line up
     let num_cols = rows[0].len();
     let mut widths: Vec<usize> = vec![0; num_cols];
 
-    for row in rows {
+    for row in rows.iter().skip(usize::from(!headers)) {
         for (i, cell) in row.iter().enumerate() {
             if i < num_cols {
-                widths[i] = widths[i].max(cell.len());
+                widths[i] = widths[i].max(cell.width());
             }
         }
     }
@@ -53,14 +200,28 @@ fn format_rows(rows: &[Vec<String>]) -> String {
     let mut output = String::new();
 
     for (row_idx, row) in rows.iter().enumerate() {
+        if row_idx == 0 && !headers {
+            continue;
+        }
+
         let line: Vec<String> = row
             .iter()
             .enumerate()
             .map(|(i, cell)| {
-                if i < widths.len() {
-                    format!("{:<width$}", cell, width = widths[i])
+                let padded = if i < widths.len() {
+                    format!("{}{}", cell, " ".repeat(widths[i] - cell.width()))
                 } else {
                     cell.clone()
+                };
+                if !color {
+                    padded
+                } else if row_idx == 0 {
+                    paint(&padded, BOLD)
+                } else {
+                    match status_color(&full_rows[row_idx][i]).filter(|_| status_columns[i]) {
+                        Some(code) => paint(&padded, code),
+                        None => padded,
+                    }
                 }
             })
             .collect();
@@ -87,23 +248,188 @@ mod tests {
     #[test]
     fn test_format_table_empty() {
         let emails: Vec<Email> = vec![];
-        let output = format_table(&emails);
+        let output = format_table(&emails, &OutputOptions::default()).unwrap();
         assert_eq!(output, "No results found.");
     }
 
     #[test]
     fn test_format_table_with_items() {
         let emails = vec![Email {
-            id: "email-123".to_string(),
             from: Some("from@example.com".to_string()),
             to: Some(vec!["to@example.com".to_string()]),
             subject: Some("Test Subject".to_string()),
             created_at: Some("2025-01-15".to_string()),
             last_event: Some("delivered".to_string()),
+            ..Email::fixture("email-123")
         }];
-        let output = format_table(&emails);
+        let output = format_table(&emails, &OutputOptions::default()).unwrap();
         assert!(output.contains("email-123"));
         assert!(output.contains("Test Subject"));
         assert!(output.contains("delivered"));
+        assert!(!output.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_format_table_colors_headers_and_status() {
+        let emails: Vec<Email> = ["delivered", "bounced", "opened"]
+            .iter()
+            .map(|event| Email {
+                subject: Some("bounced".to_string()),
+                last_event: Some(event.to_string()),
+                ..Email::fixture("email-1")
+            })
+            .collect();
+        let color = OutputOptions {
+            color: true,
+            ..Default::default()
+        };
+        let output = format_table(&emails, &color).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].starts_with("\x1b[1mID"));
+        assert!(lines[2].contains("\x1b[32mdelivered"));
+        assert!(lines[3].contains("\x1b[31mbounced"));
+        assert!(!lines[4].contains("\x1b[33m"));
+        // Only the status column is colored, not a subject that happens to match
+        assert_eq!(lines[3].matches('\x1b').count(), 2);
+
+        let plain = format_table(&emails, &OutputOptions::default()).unwrap();
+        let strip = output
+            .replace("\x1b[1m", "")
+            .replace("\x1b[32m", "")
+            .replace("\x1b[31m", "")
+            .replace("\x1b[0m", "");
+        assert_eq!(strip, plain);
+    }
+
+    #[test]
+    fn test_format_table_columns() {
+        let emails = vec![Email {
+            from: Some("from@example.com".to_string()),
+            subject: Some("Hi".to_string()),
+            last_event: Some("sent".to_string()),
+            ..Email::fixture("email-1")
+        }];
+        let options = OutputOptions {
+            columns: Some(vec!["status".to_string(), "ID".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_table(&emails, &options).unwrap(),
+            "STATUS  ID     \n------  -------\nsent    email-1"
+        );
+        assert_eq!(
+            format_single(&emails[0], &options).unwrap(),
+            "STATUS: sent\nID: email-1\n"
+        );
+
+        let options = OutputOptions {
+            columns: Some(vec!["size".to_string()]),
+            ..Default::default()
+        };
+        let err = format_table(&emails, &options).unwrap_err().to_string();
+        assert!(err.starts_with("Unknown column 'size'; valid columns: ID, "));
+    }
+
+    #[test]
+    fn test_format_table_no_headers() {
+        let emails: Vec<Email> = ["email-1", "email-22"]
+            .iter()
+            .map(|id| Email {
+                last_event: Some("sent".to_string()),
+                ..Email::fixture(id)
+            })
+            .collect();
+        let options = OutputOptions {
+            columns: Some(vec!["ID".to_string(), "STATUS".to_string()]),
+            no_headers: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_table(&emails, &options).unwrap(),
+            "email-1   sent\nemail-22  sent"
+        );
+        assert_eq!(format_table::<Email>(&[], &options).unwrap(), "");
+        assert_eq!(
+            format_single(&emails[0], &options).unwrap(),
+            "email-1\nsent\n"
+        );
+    }
+
+    #[test]
+    fn test_format_table_aligns_multibyte_text() {
+        let emails: Vec<Email> = ["Café ☕", "日本語の件名", "Plain"]
+            .iter()
+            .map(|subject| Email {
+                subject: Some(subject.to_string()),
+                last_event: Some("sent".to_string()),
+                ..Email::fixture("e")
+            })
+            .collect();
+        let options = OutputOptions {
+            columns: Some(vec!["SUBJECT".to_string(), "STATUS".to_string()]),
+            ..Default::default()
+        };
+        let output = format_table(&emails, &options).unwrap();
+        let status_at: Vec<usize> = output
+            .lines()
+            .filter_map(|line| {
+                line.find("sent")
+                    .or_else(|| line.find("STATUS"))
+                    .map(|end| line[..end].width())
+            })
+            .collect();
+        // Widest subject is 12 columns (CJK characters are two wide), plus the gap
+        assert_eq!(status_at, vec![14, 14, 14, 14]);
+    }
+
+    #[test]
+    fn test_max_width_keeps_status_colors() {
+        let rows = vec![
+            vec!["ID".to_string(), "STATUS".to_string()],
+            vec!["email-1".to_string(), "delivered".to_string()],
+        ];
+        let output = format_rows(&rows, true, true, Some(4));
+        assert!(output.contains(&paint("del…", GREEN)), "{output:?}");
+    }
+
+    #[test]
+    fn test_max_width_keeps_status_color_for_single_item() {
+        let email = Email {
+            last_event: Some("delivered".to_string()),
+            ..Email::fixture("email-1")
+        };
+        let options = OutputOptions {
+            columns: Some(vec!["STATUS".to_string()]),
+            color: true,
+            max_width: Some(4),
+            ..Default::default()
+        };
+        let output = format_single(&email, &options).unwrap();
+        assert!(output.contains(&paint("del…", GREEN)), "{output:?}");
+    }
+
+    #[test]
+    fn test_truncate_respects_display_width() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(
+            truncate("v=spf1 include:amazonses.com ~all", 10),
+            "v=spf1 in…"
+        );
+        assert_eq!(truncate("日本語の件名", 7), "日本語…");
+        assert_eq!(truncate("ééééé", 3), "éé…");
+        assert_eq!(truncate("abc", 1), "…");
+        assert_eq!(truncate("abc", 0), "");
+    }
+
+    #[test]
+    fn test_format_ids() {
+        let emails: Vec<Email> = ["email-1", "email-2"]
+            .iter()
+            .map(|id| Email {
+                subject: Some("Ignored".to_string()),
+                ..Email::fixture(id)
+            })
+            .collect();
+        assert_eq!(format_ids(&emails), "email-1\nemail-2");
     }
 }
@@ -0,0 +1,130 @@
+// ABOUTME: Custom per-row output using {COLUMN} placeholders.
+// ABOUTME: Used with --output-template to print table columns in any layout.
+
+use anyhow::{bail, Result};
+
+use crate::types::Tabular;
+
+/// A parsed template: literal text and the columns to fill in between it
+enum Piece {
+    Text(String),
+    Column(usize),
+}
+
+/// Render each item on its own line by filling `{COLUMN}` placeholders
+///
+/// Column names are the table headers, matched case-insensitively. `{{` and
+/// `}}` print literal braces, and `\t` and `\n` print a tab and a newline.
+pub fn format_template<T: Tabular>(items: &[T], template: &str) -> Result<String> {
+    let pieces = parse(template, &T::headers())?;
+
+    Ok(items
+        .iter()
+        .map(|item| {
+            let row = item.row();
+            pieces
+                .iter()
+                .map(|piece| match piece {
+                    Piece::Text(text) => text.as_str(),
+                    Piece::Column(index) => row[*index].as_str(),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn parse(template: &str, headers: &[&str]) -> Result<Vec<Piece>> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '\\' if matches!(chars.peek(), Some('t' | 'n')) => {
+                text.push(if chars.next() == Some('t') {
+                    '\t'
+                } else {
+                    '\n'
+                });
+            }
+            '{' => {
+                let mut name = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+                    name.push(c);
+                }
+                if !closed {
+                    bail!("Unclosed '{{' in output template; use '{{{{' for a literal brace");
+                }
+                let Some(index) = headers
+                    .iter()
+                    .position(|h| h.eq_ignore_ascii_case(name.trim()))
+                else {
+                    bail!(
+                        "Unknown placeholder '{{{}}}' in output template; valid columns: {}",
+                        name,
+                        headers.join(", ")
+                    );
+                };
+                pieces.push(Piece::Text(std::mem::take(&mut text)));
+                pieces.push(Piece::Column(index));
+            }
+            _ => text.push(c),
+        }
+    }
+
+    pieces.push(Piece::Text(text));
+    Ok(pieces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Contact;
+
+    fn contacts() -> Vec<Contact> {
+        serde_json::from_value(serde_json::json!([
+            {"id": "c-1", "email": "ada@example.com", "first_name": "Ada", "unsubscribed": false},
+            {"id": "c-2", "email": "bob@example.com", "unsubscribed": true}
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_format_template_fills_columns() {
+        assert_eq!(
+            format_template(&contacts(), "{ID}: {email} ({First Name})").unwrap(),
+            "c-1: ada@example.com (Ada)\nc-2: bob@example.com ()"
+        );
+        assert_eq!(
+            format_template(&contacts(), r"{{{ID}}}\t{UNSUBSCRIBED}").unwrap(),
+            "{c-1}\tfalse\n{c-2}\ttrue"
+        );
+    }
+
+    #[test]
+    fn test_format_template_rejects_unknown_placeholder() {
+        let err = format_template(&contacts(), "{ID} {STATUS}")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Unknown placeholder '{STATUS}' in output template; valid columns: ID, EMAIL, FIRST NAME, LAST NAME, UNSUBSCRIBED, CREATED"
+        );
+        assert!(format_template::<Contact>(&[], "{nope}").is_err());
+        assert!(format_template(&contacts(), "{ID").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+// ABOUTME: TOML formatting for structured output.
+// ABOUTME: Used with --format toml; lists are emitted as an [[items]] array.
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+/// Key holding the array of tables when a list is rendered as TOML
+pub const LIST_KEY: &str = "items";
+
+/// Format a single object as a TOML document
+///
+/// TOML has no null, so missing fields are left out.
+pub fn format_toml_single(value: &Value) -> Result<String> {
+    let value = without_nulls(value);
+    if !value.is_object() {
+        bail!("TOML output needs an object; drop --select or use --format json");
+    }
+    Ok(toml::to_string(&value)?)
+}
+
+/// Format a list as a document with one `[[items]]` table per element
+pub fn format_toml_list(items: &Value) -> Result<String> {
+    let mut document = serde_json::Map::new();
+    document.insert(LIST_KEY.to_string(), items.clone());
+    format_toml_single(&Value::Object(document))
+}
+
+/// Drop null fields and array entries, which TOML cannot represent
+fn without_nulls(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k.clone(), without_nulls(v)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .filter(|v| !v.is_null())
+                .map(without_nulls)
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DnsRecord, Domain, Template};
+
+    #[test]
+    fn test_domain_round_trip() {
+        let domain = Domain {
+            id: "dom-1".to_string(),
+            name: "example.com".to_string(),
+            status: Some("verified".to_string()),
+            region: None,
+            records: Some(vec![DnsRecord {
+                record: "SPF".to_string(),
+                name: "send".to_string(),
+                r#type: Some("TXT".to_string()),
+                ttl: Some("Auto".to_string()),
+                value: "v=spf1 include:amazonses.com ~all".to_string(),
+                status: None,
+                priority: Some(10),
+            }]),
+        };
+
+        let output = format_toml_single(&serde_json::to_value(&domain).unwrap()).unwrap();
+        let parsed: Domain = toml::from_str(&output).unwrap();
+
+        assert_eq!(parsed.id, domain.id);
+        assert_eq!(parsed.region, None);
+        let record = &parsed.records.unwrap()[0];
+        assert_eq!(record.value, "v=spf1 include:amazonses.com ~all");
+        assert_eq!(record.priority, Some(10));
+    }
+
+    #[test]
+    fn test_template_list_round_trip() {
+        #[derive(serde::Deserialize)]
+        struct Document {
+            items: Vec<Template>,
+        }
+
+        let templates = vec![Template {
+            id: "tmpl-1".to_string(),
+            name: "Welcome".to_string(),
+            subject: Some("Hi {{name}}".to_string()),
+            html: Some("<p>Hello</p>".to_string()),
+            text: None,
+            created_at: None,
+        }];
+
+        let output = format_toml_list(&serde_json::to_value(&templates).unwrap()).unwrap();
+        assert!(output.starts_with("[[items]]"));
+
+        let parsed: Document = toml::from_str(&output).unwrap();
+        assert_eq!(parsed.items[0].subject.as_deref(), Some("Hi {{name}}"));
+        assert_eq!(parsed.items[0].html.as_deref(), Some("<p>Hello</p>"));
+    }
+
+    #[test]
+    fn test_scalar_is_rejected() {
+        assert!(format_toml_single(&serde_json::json!("verified")).is_err());
+    }
+}
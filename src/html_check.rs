@@ -0,0 +1,131 @@
+// ABOUTME: Lightweight HTML structure checks for email templates.
+// ABOUTME: Flags unclosed, unexpected, and unterminated tags with line numbers.
+
+use crate::templating::line_at;
+
+/// Elements that never take a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose contents are raw text, not markup
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+/// Check that every opened tag is closed in order
+///
+/// Returns `(line, message)` pairs with 1-based line numbers. This is not a
+/// full HTML parser: optional end tags (e.g. `</li>`) are still expected.
+pub fn check_html(source: &str) -> Vec<(usize, String)> {
+    let mut issues = Vec::new();
+    let mut open: Vec<(String, usize)> = Vec::new();
+    let mut pos = 0;
+
+    while let Some(offset) = source[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &source[start..];
+
+        if rest.starts_with("<!--") {
+            match rest.find("-->") {
+                Some(end) => pos = start + end + 3,
+                None => {
+                    issues.push((line_at(source, start), "Unclosed comment".to_string()));
+                    break;
+                }
+            }
+            continue;
+        }
+
+        let Some(end) = rest.find('>') else {
+            issues.push((line_at(source, start), "Unterminated tag".to_string()));
+            break;
+        };
+        pos = start + end + 1;
+
+        let tag = &rest[1..end];
+        if tag.starts_with(['!', '?']) {
+            continue;
+        }
+
+        let (closing, body) = match tag.strip_prefix('/') {
+            Some(body) => (true, body),
+            None => (false, tag),
+        };
+        let name = body
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if name.is_empty() || !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            continue;
+        }
+
+        let line = line_at(source, start);
+
+        if !closing {
+            if VOID_ELEMENTS.contains(&name.as_str()) || tag.ends_with('/') {
+                continue;
+            }
+            if RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+                let close = format!("</{name}");
+                match source[pos..].to_ascii_lowercase().find(&close) {
+                    Some(end) => pos += end,
+                    None => {
+                        issues.push((line, format!("Unclosed <{name}>")));
+                        break;
+                    }
+                }
+            }
+            open.push((name, line));
+            continue;
+        }
+
+        match open.iter().rposition(|(n, _)| *n == name) {
+            Some(index) => {
+                for (unclosed, opened) in open.drain(index..).skip(1) {
+                    issues.push((opened, format!("Unclosed <{unclosed}>")));
+                }
+            }
+            None => issues.push((line, format!("Unexpected </{name}>"))),
+        }
+    }
+
+    for (name, line) in open {
+        issues.push((line, format!("Unclosed <{name}>")));
+    }
+
+    issues.sort_by_key(|(line, _)| *line);
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_html_accepts_well_formed() {
+        let html = "<!DOCTYPE html>\n<html><body>\n<!-- <p> -->\n<p>Hi<br>{{name}}<img src=\"x\" /></p>\n<style>p > a { color: red }</style>\n</body></html>";
+        assert!(check_html(html).is_empty());
+    }
+
+    #[test]
+    fn test_check_html_reports_unclosed_and_unexpected() {
+        let html = "<div>\n<p>Hello\n</div>\n</span>\n<table>";
+        assert_eq!(
+            check_html(html),
+            vec![
+                (2, "Unclosed <p>".to_string()),
+                (4, "Unexpected </span>".to_string()),
+                (5, "Unclosed <table>".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_html_unterminated_tag() {
+        assert_eq!(
+            check_html("<p>Hi</p>\n<a href=\"x\""),
+            vec![(2, "Unterminated tag".to_string())]
+        );
+    }
+}
@@ -0,0 +1,69 @@
+// ABOUTME: Library interface for the Resend CLI crate.
+// ABOUTME: Exposes the HTTP client, API types, and config for use from other programs.
+
+//! Client and types for the [Resend](https://resend.com) email API.
+//!
+//! The `resend` binary is a thin wrapper around this crate; the same client
+//! can be used directly:
+//!
+//! ```no_run
+//! use resend::{ResendClient, SendEmailRequest};
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let client = ResendClient::builder("re_123").build()?;
+//!
+//! let request = SendEmailRequest {
+//!     from: "sender@example.com".to_string(),
+//!     to: vec!["recipient@example.com".to_string()],
+//!     subject: "Hello".to_string(),
+//!     html: None,
+//!     text: Some("Plain text body".to_string()),
+//!     cc: None,
+//!     bcc: None,
+//!     reply_to: None,
+//!     scheduled_at: None,
+//!     headers: None,
+//!     attachments: None,
+//!     tags: None,
+//! };
+//! let sent = client.send_email(request, None).await?;
+//! println!("{}", sent.id);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Errors from the API downcast to [`ApiError`]; use
+//! [`client::request_id`] to get the request ID to quote to support.
+
+pub mod client;
+pub mod config;
+pub mod types;
+
+// Internals the binary builds on; not part of the supported API.
+#[doc(hidden)]
+pub mod checkpoint;
+#[doc(hidden)]
+pub mod commands;
+#[doc(hidden)]
+pub mod concurrency;
+#[doc(hidden)]
+pub mod dates;
+#[doc(hidden)]
+pub mod dedup;
+#[doc(hidden)]
+pub mod dns_check;
+#[doc(hidden)]
+pub mod formatters;
+#[doc(hidden)]
+pub mod html_check;
+mod redact;
+#[doc(hidden)]
+pub mod temp_files;
+#[doc(hidden)]
+pub mod templating;
+#[doc(hidden)]
+pub mod validation;
+
+pub use client::{ApiError, ResendClient, ResendClientBuilder, RetryPolicy, StatusMatch};
+pub use config::Config;
+pub use types::*;
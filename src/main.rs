@@ -1,20 +1,24 @@
 // ABOUTME: Entry point for the Resend CLI.
 // ABOUTME: Parses arguments and dispatches to command handlers.
 
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use anyhow::{Context, Result};
+use clap::{Command, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-mod client;
-mod commands;
-mod config;
-mod formatters;
-mod types;
-
-use commands::api_keys::ApiKeysCommands;
-use commands::config::ConfigCommands;
-use commands::domains::DomainsCommands;
-use commands::emails::EmailsCommands;
-use commands::templates::TemplatesCommands;
+use resend::commands::api_keys::ApiKeysCommands;
+use resend::commands::audiences::AudiencesCommands;
+use resend::commands::broadcasts::BroadcastsCommands;
+use resend::commands::config::ConfigCommands;
+use resend::commands::contacts::ContactsCommands;
+use resend::commands::domains::DomainsCommands;
+use resend::commands::emails::EmailsCommands;
+use resend::commands::templates::TemplatesCommands;
+use resend::commands::{self, CommonArgs, GlobalArgs};
+use resend::formatters;
+use resend::types::OutputFormat;
 
 /// Resend CLI - Command-line interface for the Resend email platform
 #[derive(Parser)]
@@ -24,11 +28,15 @@ use commands::templates::TemplatesCommands;
 #[command(about = "Command-line interface for the Resend email platform", long_about = None)]
 #[command(propagate_version = true)]
 struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
 enum Commands {
     /// Manage configuration profiles
     #[command(subcommand)]
@@ -49,18 +57,222 @@ enum Commands {
     /// Manage email templates
     #[command(subcommand)]
     Templates(TemplatesCommands),
+
+    /// Manage audiences (contact lists)
+    #[command(subcommand)]
+    Audiences(AudiencesCommands),
+
+    /// Manage the contacts in an audience
+    #[command(subcommand)]
+    Contacts(ContactsCommands),
+
+    /// Create and send broadcasts to an audience
+    #[command(subcommand)]
+    Broadcasts(BroadcastsCommands),
+
+    /// Show the active profile, API key, and its permission level
+    Whoami {
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
+    /// Print the man page, or write one per command into a directory
+    #[command(hide = true)]
+    Man {
+        /// Directory for resend.1, resend-emails.1, resend-emails-send.1, ...
+        #[arg(short, long, value_name = "DIR")]
+        output: Option<PathBuf>,
+    },
+}
+
+impl Commands {
+    /// Common arguments of the chosen command, if it takes them
+    fn common_mut(&mut self) -> Option<&mut CommonArgs> {
+        match self {
+            Commands::Config(_) | Commands::Completions { .. } | Commands::Man { .. } => None,
+            Commands::Emails(cmd) => Some(cmd.common_mut()),
+            Commands::Domains(cmd) => Some(cmd.common_mut()),
+            Commands::ApiKeys(cmd) => Some(cmd.common_mut()),
+            Commands::Templates(cmd) => Some(cmd.common_mut()),
+            Commands::Audiences(cmd) => Some(cmd.common_mut()),
+            Commands::Contacts(cmd) => Some(cmd.common_mut()),
+            Commands::Broadcasts(cmd) => Some(cmd.common_mut()),
+            Commands::Whoami { common } => Some(common),
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let _ = dotenvy::dotenv();
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+
+    if let Commands::Config(cmd) = &mut cli.command {
+        cmd.inherit(&cli.global)?;
+    }
+    let common = cli.command.common_mut().map(|common| {
+        common.inherit(&cli.global);
+        common.clone()
+    });
+
+    let json_errors = wants_json_errors(&cli.command, common.as_ref());
 
-    match cli.command {
+    let result = match cli.command {
         Commands::Config(cmd) => cmd.execute().await,
         Commands::Emails(cmd) => cmd.execute().await,
         Commands::Domains(cmd) => cmd.execute().await,
         Commands::ApiKeys(cmd) => cmd.execute().await,
         Commands::Templates(cmd) => cmd.execute().await,
+        Commands::Audiences(cmd) => cmd.execute().await,
+        Commands::Contacts(cmd) => cmd.execute().await,
+        Commands::Broadcasts(cmd) => cmd.execute().await,
+        Commands::Whoami { common } => commands::whoami::execute(&common).await,
+        Commands::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "resend", &mut std::io::stdout());
+            Ok(())
+        }
+        Commands::Man { output } => match output {
+            Some(dir) => write_man_pages(&dir),
+            None => {
+                let mut page = Vec::new();
+                clap_mangen::Man::new(Cli::command()).render(&mut page)?;
+                std::io::stdout().write_all(&page)?;
+                Ok(())
+            }
+        },
+    };
+
+    if let Err(err) = &result {
+        if json_errors {
+            formatters::errors::output_json_error(err);
+            std::process::exit(1);
+        }
+        if common.as_ref().is_some_and(|common| common.pretty_errors) {
+            eprintln!("{}", formatters::errors::render_error(err));
+            std::process::exit(1);
+        }
+    }
+
+    result
+}
+
+/// Whether a failure of `command` should be reported in the JSON envelope
+fn wants_json_errors(command: &Commands, common: Option<&CommonArgs>) -> bool {
+    match (command, common) {
+        (Commands::Config(cmd), _) => cmd.json(),
+        (_, Some(common)) => {
+            common.json_errors
+                || matches!(common.format(), OutputFormat::Json | OutputFormat::Ndjson)
+        }
+        _ => false,
+    }
+}
+
+/// Write a man page for the CLI and for every subcommand into `dir`
+fn write_man_pages(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    let mut cli = Cli::command();
+    cli.build();
+
+    let mut written = 0;
+    render_man_pages(&cli, dir, &mut written)?;
+    eprintln!("Wrote {} man pages to {}", written, dir.display());
+    Ok(())
+}
+
+/// Render `cmd` and, recursively, its visible subcommands as
+/// `<parent>-<name>.1`
+fn render_man_pages(cmd: &Command, dir: &Path, written: &mut usize) -> Result<()> {
+    let name = cmd.get_name().to_string();
+    let mut page = Vec::new();
+    clap_mangen::Man::new(cmd.clone()).render(&mut page)?;
+
+    let path = dir.join(format!("{}.1", name));
+    fs::write(&path, page).with_context(|| format!("Failed to write {}", path.display()))?;
+    *written += 1;
+
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        if sub.get_name() == "help" {
+            continue;
+        }
+        let sub = sub.clone().name(format!("{}-{}", name, sub.get_name()));
+        render_man_pages(&sub, dir, written)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_failed_config_setup_with_json_reports_json() {
+        for args in [
+            &["resend", "config", "setup", "--json"][..],
+            &["resend", "--json", "config", "setup"],
+        ] {
+            let mut cli = Cli::try_parse_from(args).unwrap();
+            if let Commands::Config(cmd) = &mut cli.command {
+                cmd.inherit(&cli.global).unwrap();
+            }
+            assert!(wants_json_errors(&cli.command, None), "{args:?}");
+        }
+
+        let cli = Cli::try_parse_from(["resend", "config", "setup"]).unwrap();
+        assert!(!wants_json_errors(&cli.command, None));
+    }
+
+    #[test]
+    fn test_global_format_yields_to_output_template() {
+        let mut cli = Cli::try_parse_from([
+            "resend",
+            "--format",
+            "csv",
+            "emails",
+            "list",
+            "--output-template",
+            "{ID}",
+        ])
+        .unwrap();
+        let global = cli.global.clone();
+        let common = cli.command.common_mut().unwrap();
+        common.inherit(&global);
+        assert_eq!(common.format(), OutputFormat::Table);
+        assert_eq!(common.output_template.as_deref(), Some("{ID}"));
+    }
+
+    #[test]
+    fn test_completions_cover_subcommands() {
+        let mut script = Vec::new();
+        clap_complete::generate(Shell::Bash, &mut Cli::command(), "resend", &mut script);
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("broadcasts"));
+        assert!(script.contains("--idempotency-key"));
+    }
+
+    #[test]
+    fn test_man_pages_cover_subcommands() {
+        let dir = tempfile::tempdir().unwrap();
+        write_man_pages(dir.path()).unwrap();
+
+        let top = fs::read_to_string(dir.path().join("resend.1")).unwrap();
+        assert!(top.contains(".TH resend"));
+        let send = fs::read_to_string(dir.path().join("resend-emails-send.1")).unwrap();
+        assert!(send.contains("idempotency"));
+        assert!(!dir.path().join("resend-man.1").exists());
     }
 }
@@ -0,0 +1,106 @@
+// ABOUTME: Redaction of secrets before anything is logged.
+// ABOUTME: Scrubs bearer tokens and token fields from headers and bodies.
+
+use serde_json::Value;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// JSON object keys whose values are always secret
+const SECRET_KEYS: &[&str] = &["token", "api_key", "authorization"];
+
+/// Replace the value of any secret field, at any depth, with a placeholder
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, val) in map.iter_mut() {
+                if SECRET_KEYS.contains(&key.to_ascii_lowercase().as_str()) {
+                    *val = Value::String(REDACTED.to_string());
+                } else {
+                    redact(val);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                redact(item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Redact a header value for display, hiding credentials
+pub fn redact_header(name: &str, value: &str) -> String {
+    if name.eq_ignore_ascii_case("authorization") {
+        match value.split_once(' ') {
+            Some((scheme, _)) => format!("{scheme} {REDACTED}"),
+            None => REDACTED.to_string(),
+        }
+    } else {
+        value.to_string()
+    }
+}
+
+/// Redact a raw request or response body for display
+///
+/// JSON bodies have their secret fields replaced; any literal occurrence of
+/// the API key is scrubbed regardless of whether the body parses.
+pub fn redact_body(body: &str, api_key: &str) -> String {
+    let redacted = match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            redact(&mut value);
+            serde_json::to_string(&value).unwrap_or_default()
+        }
+        Err(_) => body.to_string(),
+    };
+
+    if api_key.is_empty() {
+        redacted
+    } else {
+        redacted.replace(api_key, REDACTED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_nested_token_fields() {
+        let mut value = serde_json::json!({
+            "data": [{"id": "key-1", "token": "re_secret_1"}],
+            "meta": {"api_key": "re_secret_2", "name": "kept"}
+        });
+        redact(&mut value);
+        let output = value.to_string();
+        assert!(!output.contains("re_secret_1"));
+        assert!(!output.contains("re_secret_2"));
+        assert!(output.contains("kept"));
+    }
+
+    #[test]
+    fn test_api_key_create_response_never_logs_token() {
+        let body = r#"{"id":"dacf4072-4119-4d88-932f-6202748ac7c8","token":"re_c1tpEyD8_NKFusih9vKVQknRAQfmFcWCv"}"#;
+        let logged = redact_body(body, "re_caller_key");
+        assert!(!logged.contains("re_c1tpEyD8_NKFusih9vKVQknRAQfmFcWCv"));
+        assert!(logged.contains("dacf4072-4119-4d88-932f-6202748ac7c8"));
+    }
+
+    #[test]
+    fn test_redact_body_scrubs_api_key_in_plain_text() {
+        let logged = redact_body("invalid key re_caller_key", "re_caller_key");
+        assert_eq!(logged, "invalid key [REDACTED]");
+    }
+
+    #[test]
+    fn test_redact_header_bearer() {
+        assert_eq!(
+            redact_header("Authorization", "Bearer re_123456789"),
+            "Bearer [REDACTED]"
+        );
+        assert_eq!(
+            redact_header("Content-Type", "application/json"),
+            "application/json"
+        );
+    }
+}
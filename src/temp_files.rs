@@ -0,0 +1,181 @@
+// ABOUTME: Temp files for the preview and editor workflows.
+// ABOUTME: Files are either removed on drop (surviving Ctrl-C cleanly) or kept and pruned later.
+
+use std::fs;
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Context, Result};
+use tempfile::NamedTempFile;
+
+/// Write `contents` to a new private temp file, which is removed when dropped
+///
+/// Only drop it once nothing else needs the file. To hand the path to another
+/// process that outlives this one, use [`write_kept_in`] instead.
+pub fn write_temp(prefix: &str, suffix: &str, contents: &str) -> Result<NamedTempFile> {
+    write_temp_in(&std::env::temp_dir(), prefix, suffix, contents)
+}
+
+/// Like [`write_temp`], but in `dir`, so the file can later be persisted over
+/// another file there with an atomic rename
+pub fn write_temp_in(
+    dir: &Path,
+    prefix: &str,
+    suffix: &str,
+    contents: &str,
+) -> Result<NamedTempFile> {
+    let mut file = tempfile::Builder::new()
+        .prefix(prefix)
+        .suffix(suffix)
+        .tempfile_in(dir)
+        .with_context(|| format!("Failed to create temp file in {}", dir.display()))?;
+    file.write_all(contents.as_bytes())
+        .with_context(|| format!("Failed to write temp file: {}", file.path().display()))?;
+    Ok(file)
+}
+
+/// Write `contents` to a new private file in `dir` that is kept after this
+/// process exits, returning its path
+///
+/// For files another program reads in its own time, such as a browser
+/// opening a preview. Pair with [`remove_stale`] so they don't pile up.
+pub fn write_kept_in(dir: &Path, prefix: &str, suffix: &str, contents: &str) -> Result<PathBuf> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    let (_, path) = write_temp_in(dir, prefix, suffix, contents)?
+        .keep()
+        .context("Failed to keep temp file")?;
+    Ok(path)
+}
+
+/// Remove files in `dir` whose names start with `prefix` and that were last
+/// modified more than `max_age` ago, returning how many went
+///
+/// Best effort: unreadable entries and failed removals are skipped.
+pub fn remove_stale(dir: &Path, prefix: &str, max_age: Duration) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let now = SystemTime::now();
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age > max_age)
+        })
+        .filter(|entry| fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+/// Run `task`, giving up with an error if Ctrl-C arrives first
+///
+/// Without this the default SIGINT handling exits on the spot and temp files
+/// held by the caller are never dropped. Returning an error lets them be
+/// removed as it propagates.
+///
+/// This only protects a file while `task` is still using it, so `task` must
+/// not finish until the file is done with, as an editor that blocks until
+/// it exits does. Launchers that return at once, like `xdg-open`, finish
+/// before the file is read; use [`write_kept_in`] for those.
+pub async fn until_interrupted<T>(task: impl Future<Output = Result<T>>) -> Result<T> {
+    race(task, tokio::signal::ctrl_c()).await
+}
+
+/// Run `task` unless `cancel` completes successfully first
+async fn race<T>(
+    task: impl Future<Output = Result<T>>,
+    cancel: impl Future<Output = std::io::Result<()>>,
+) -> Result<T> {
+    tokio::select! {
+        result = task => result,
+        // If no handler could be installed, carry on without one
+        Ok(()) = cancel => Err(anyhow!("Interrupted")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hold a temp file while `task` runs against `cancel`, like a command
+    async fn run_with_temp(
+        path: &mut Option<PathBuf>,
+        task: impl Future<Output = Result<()>>,
+        cancel: impl Future<Output = std::io::Result<()>>,
+    ) -> Result<()> {
+        let file = write_temp("resend-test-", ".html", "<p>Preview</p>")?;
+        *path = Some(file.path().to_path_buf());
+        assert_eq!(
+            std::fs::read_to_string(file.path()).unwrap(),
+            "<p>Preview</p>"
+        );
+        race(task, cancel).await
+    }
+
+    #[test]
+    fn test_write_temp_names_and_keep() {
+        let file = write_temp("resend-preview-", ".html", "<p>Kept</p>").unwrap();
+        let name = file.path().file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with("resend-preview-") && name.ends_with(".html"));
+
+        let (_, kept) = file.keep().unwrap();
+        assert!(kept.exists());
+        std::fs::remove_file(kept).unwrap();
+    }
+
+    #[test]
+    fn test_write_kept_in_and_remove_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let previews = dir.path().join("previews");
+        let kept = write_kept_in(&previews, "resend-preview-", ".html", "<p>Kept</p>").unwrap();
+        assert_eq!(std::fs::read_to_string(&kept).unwrap(), "<p>Kept</p>");
+
+        fs::write(previews.join("other.html"), "").unwrap();
+        assert_eq!(
+            remove_stale(&previews, "resend-preview-", Duration::from_secs(60)),
+            0
+        );
+        assert!(kept.exists());
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(
+            remove_stale(&previews, "resend-preview-", Duration::from_millis(10)),
+            1
+        );
+        assert!(!kept.exists());
+        assert!(previews.join("other.html").exists());
+    }
+
+    #[tokio::test]
+    async fn test_temp_file_removed_after_completion() {
+        let mut path = None;
+        run_with_temp(&mut path, async { Ok(()) }, std::future::pending())
+            .await
+            .unwrap();
+        assert!(!path.unwrap().exists());
+    }
+
+    #[tokio::test]
+    async fn test_temp_file_removed_when_cancelled() {
+        let mut path = None;
+        let err = run_with_temp(&mut path, std::future::pending(), async { Ok(()) })
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Interrupted");
+        assert!(!path.unwrap().exists());
+    }
+
+    #[tokio::test]
+    async fn test_failed_cancel_handler_is_ignored() {
+        let failed = async { Err(std::io::Error::other("no signal handler")) };
+        let result = race(async { Ok(7) }, failed).await.unwrap();
+        assert_eq!(result, 7);
+    }
+}
@@ -0,0 +1,221 @@
+// ABOUTME: Handlebars-style placeholder scanning for email templates.
+// ABOUTME: Finds, checks, and fills in the {{variable}} names a template expects.
+
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+
+/// Find placeholder names in the order they first appear
+///
+/// Accepts `{{name}}`, `{{ name }}` and triple-brace `{{{name}}}`. Section
+/// tags (`{{#if}}`, `{{/if}}`, `{{^x}}`), partials and comments are skipped.
+pub fn extract_variables(source: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = source;
+
+    while let Some(start) = rest.find("{{") {
+        let after_open = rest[start + 2..].trim_start_matches('{');
+        let Some(end) = after_open.find("}}") else {
+            break;
+        };
+
+        let name = after_open[..end].trim();
+        let is_tag = name.starts_with(['#', '/', '^', '!', '>']);
+        if !name.is_empty() && !is_tag && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+
+        rest = after_open[end + 2..].trim_start_matches('}');
+    }
+
+    names
+}
+
+/// Replace every `{{name}}` placeholder with its value from `vars`
+///
+/// Section tags and comments are left untouched. Fails listing every
+/// placeholder that has no value.
+pub fn render(source: &str, vars: &HashMap<String, String>) -> Result<String> {
+    let mut output = String::with_capacity(source.len());
+    let mut missing: Vec<String> = Vec::new();
+    let mut rest = source;
+
+    while let Some(start) = rest.find("{{") {
+        let after_open = rest[start + 2..].trim_start_matches('{');
+        let Some(end) = after_open.find("}}") else {
+            break;
+        };
+        let after_close = after_open[end + 2..].trim_start_matches('}');
+        let tag_len = rest.len() - after_close.len();
+
+        let name = after_open[..end].trim();
+        let is_tag = name.starts_with(['#', '/', '^', '!', '>']);
+
+        output.push_str(&rest[..start]);
+        match vars.get(name) {
+            Some(value) if !is_tag => output.push_str(value),
+            _ => {
+                if !is_tag && !missing.iter().any(|m| m == name) {
+                    missing.push(name.to_string());
+                }
+                output.push_str(&rest[start..tag_len]);
+            }
+        }
+
+        rest = after_close;
+    }
+    output.push_str(rest);
+
+    if !missing.is_empty() {
+        bail!("Missing template variables: {}", missing.join(", "));
+    }
+
+    Ok(output)
+}
+
+/// Check that `{{`/`}}` pairs balance and placeholder names are well-formed
+///
+/// Returns `(line, message)` pairs with 1-based line numbers.
+pub fn check_placeholders(source: &str) -> Vec<(usize, String)> {
+    let mut issues = Vec::new();
+    let mut pos = 0;
+
+    loop {
+        let rest = &source[pos..];
+        let (open, close) = (rest.find("{{"), rest.find("}}"));
+
+        let start = match (open, close) {
+            (None, None) => break,
+            (Some(o), Some(c)) if c < o => {
+                issues.push((line_at(source, pos + c), "Unmatched '}}'".to_string()));
+                pos += c + 2;
+                continue;
+            }
+            (None, Some(c)) => {
+                issues.push((line_at(source, pos + c), "Unmatched '}}'".to_string()));
+                pos += c + 2;
+                continue;
+            }
+            (Some(o), _) => pos + o,
+        };
+
+        let after_open = source[start + 2..].trim_start_matches('{');
+        let inner_start = source.len() - after_open.len();
+        let end = match (after_open.find("}}"), after_open.find("{{")) {
+            (Some(c), Some(o)) if o < c => None,
+            (c, _) => c,
+        };
+        let Some(end) = end else {
+            issues.push((line_at(source, start), "Unclosed '{{'".to_string()));
+            pos = inner_start;
+            continue;
+        };
+
+        if let Some(problem) = check_tag(after_open[..end].trim()) {
+            issues.push((line_at(source, start), problem));
+        }
+
+        let after_close = after_open[end + 2..].trim_start_matches('}');
+        pos = source.len() - after_close.len();
+    }
+
+    issues
+}
+
+/// Describe what is wrong with the inside of a `{{...}}` tag, if anything
+fn check_tag(tag: &str) -> Option<String> {
+    if tag.is_empty() {
+        return Some("Empty placeholder '{{}}'".to_string());
+    }
+    if tag.starts_with('!') {
+        return None;
+    }
+
+    let name = match tag.strip_prefix(['#', '/', '^', '>']) {
+        Some(section) => section.split_whitespace().next().unwrap_or_default(),
+        None => tag,
+    };
+    if is_valid_name(name) {
+        None
+    } else {
+        Some(format!("Invalid variable name '{{{{{tag}}}}}'"))
+    }
+}
+
+/// Names start with a letter or underscore, then letters, digits, `_`, `-` or `.`
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// 1-based line number of a byte offset
+pub fn line_at(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_variables_unique_in_order() {
+        let html = "<h1>Hi {{name}}</h1><p>{{ company }} welcomes {{name}}</p>";
+        assert_eq!(extract_variables(html), vec!["name", "company"]);
+    }
+
+    #[test]
+    fn test_extract_variables_triple_braces() {
+        assert_eq!(extract_variables("{{{FIRST_NAME}}}!"), vec!["FIRST_NAME"]);
+    }
+
+    #[test]
+    fn test_extract_variables_skips_sections_and_comments() {
+        let html = "{{#if vip}}Gold{{/if}}{{! note }}{{plan}}";
+        assert_eq!(extract_variables(html), vec!["plan"]);
+    }
+
+    #[test]
+    fn test_render_substitutes_values() {
+        let vars = HashMap::from([
+            ("name".to_string(), "Ada".to_string()),
+            ("company".to_string(), "Acme".to_string()),
+        ]);
+        let rendered = render("Hi {{ name }}, welcome to {{{company}}}!", &vars).unwrap();
+        assert_eq!(rendered, "Hi Ada, welcome to Acme!");
+    }
+
+    #[test]
+    fn test_render_reports_missing_variables() {
+        let vars = HashMap::from([("name".to_string(), "Ada".to_string())]);
+        let err = render("{{name}} {{plan}} {{seats}} {{plan}}", &vars).unwrap_err();
+        assert_eq!(err.to_string(), "Missing template variables: plan, seats");
+    }
+
+    #[test]
+    fn test_check_placeholders_accepts_valid_template() {
+        let html = "{{#if vip}}Hi {{ first_name }}{{/if}} {{{user.email}}} {{! note }}";
+        assert!(check_placeholders(html).is_empty());
+    }
+
+    #[test]
+    fn test_check_placeholders_reports_problems_by_line() {
+        let html = "Hi {{name}\n{{first name}}\n}} {{}}\n{{tail";
+        assert_eq!(
+            check_placeholders(html),
+            vec![
+                (1, "Unclosed '{{'".to_string()),
+                (2, "Invalid variable name '{{first name}}'".to_string()),
+                (3, "Unmatched '}}'".to_string()),
+                (3, "Empty placeholder '{{}}'".to_string()),
+                (4, "Unclosed '{{'".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_variables_unterminated() {
+        assert_eq!(extract_variables("Hello {{name"), Vec::<String>::new());
+    }
+}
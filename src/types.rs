@@ -1,8 +1,10 @@
 // ABOUTME: Data types for Resend API requests and responses.
 // ABOUTME: Includes serialization and table formatting traits.
 
+use clap::builder::PossibleValue;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Output format for CLI results
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
@@ -10,17 +12,114 @@ pub enum OutputFormat {
     #[default]
     Table,
     Json,
+    Ids,
+    Toml,
+    Csv,
+    /// One compact JSON object per line
+    Ndjson,
 }
 
+/// When to color table output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ColorChoice {
+    /// Only when writing to a terminal and `NO_COLOR` is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+pub use resend_derive::Tabular;
+
 /// Trait for types that can be displayed as tables
+///
+/// Usually derived; see `resend_derive::Tabular` for the field attributes.
 pub trait Tabular {
     fn headers() -> Vec<&'static str>;
     fn row(&self) -> Vec<String>;
+
+    /// Value of the ID column, if the type has one
+    fn id_value(&self) -> Option<String> {
+        let index = Self::headers().iter().position(|h| *h == "ID")?;
+        self.row().into_iter().nth(index)
+    }
+}
+
+impl<T: Tabular> Tabular for &T {
+    fn headers() -> Vec<&'static str> {
+        T::headers()
+    }
+
+    fn row(&self) -> Vec<String> {
+        (*self).row()
+    }
+}
+
+/// A value that can fill a table cell; `None` means missing
+pub trait Cell {
+    fn cell(&self) -> Option<String>;
 }
 
+impl Cell for String {
+    fn cell(&self) -> Option<String> {
+        Some(self.clone())
+    }
+}
+
+impl Cell for &str {
+    fn cell(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+impl Cell for Vec<String> {
+    fn cell(&self) -> Option<String> {
+        Some(self.join(", "))
+    }
+}
+
+impl<T: Cell> Cell for Option<T> {
+    fn cell(&self) -> Option<String> {
+        self.as_ref().and_then(Cell::cell)
+    }
+}
+
+macro_rules! display_cell {
+    ($($t:ty),*) => {
+        $(impl Cell for $t {
+            fn cell(&self) -> Option<String> {
+                Some(self.to_string())
+            }
+        })*
+    };
+}
+
+display_cell!(bool, i32, i64, u32, u64, usize);
+
 // === Email Types ===
 
-#[derive(Debug, Serialize)]
+/// Importance to flag an outgoing email with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Priority {
+    High,
+    Normal,
+    Low,
+}
+
+impl Priority {
+    /// The conventional `X-Priority` and `Importance` headers for this level
+    pub fn headers(&self) -> [(&'static str, &'static str); 2] {
+        match self {
+            Priority::High => [("X-Priority", "1 (Highest)"), ("Importance", "high")],
+            Priority::Normal => [("X-Priority", "3 (Normal)"), ("Importance", "normal")],
+            Priority::Low => [("X-Priority", "5 (Lowest)"), ("Importance", "low")],
+        }
+    }
+}
+
+/// Body of `POST /emails`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct SendEmailRequest {
     pub from: String,
     pub to: Vec<String>,
@@ -37,56 +136,259 @@ pub struct SendEmailRequest {
     pub reply_to: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scheduled_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<Attachment>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<Tag>>,
+}
+
+/// A name/value label on an email, reported back in analytics and webhooks
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Tag {
+    pub name: String,
+    pub value: String,
+}
+
+/// A file attached to an email: either hosted at `path`, which Resend
+/// fetches, or sent inline as base64 `content`
+///
+/// With a `content_id` the file is an inline image, shown where the HTML
+/// references `cid:<content_id>` rather than listed as an attachment.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Attachment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disposition: Option<String>,
+}
+
+impl SendEmailRequest {
+    /// Total number of to, cc, and bcc recipients
+    pub fn recipient_count(&self) -> usize {
+        self.to.len()
+            + self.cc.as_ref().map_or(0, |v| v.len())
+            + self.bcc.as_ref().map_or(0, |v| v.len())
+    }
+}
+
+/// Outcome of one row of a mail merge
+#[derive(Debug, Clone, Serialize, Tabular)]
+pub struct MergeResult {
+    pub line: u64,
+    pub to: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[tabular(name = "RESULT", default = "sent")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Outcome of one recipient's copy of an `emails send --separate`
+#[derive(Debug, Clone, Serialize, Tabular)]
+pub struct RecipientResult {
+    pub to: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[tabular(name = "RESULT", default = "sent")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
+/// Body of `PATCH /emails/{id}`: reschedule a scheduled email
 #[derive(Debug, Serialize)]
 pub struct UpdateEmailRequest {
     pub scheduled_at: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// ID of an email the API accepted for sending
+#[derive(Debug, Clone, Deserialize, Serialize, Tabular)]
 pub struct SendEmailResponse {
     pub id: String,
 }
 
+/// Envelope of `POST /emails/batch`: one ID per email, in request order
 #[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BatchSendResponse {
+    pub data: Vec<SendEmailResponse>,
+}
+
+/// An email as returned by `GET /emails` and `GET /emails/{id}`
+#[derive(Debug, Clone, Deserialize, Serialize, Tabular)]
 pub struct Email {
     pub id: String,
+    #[tabular(skip)]
     #[serde(default)]
     pub from: Option<String>,
     #[serde(default)]
     pub to: Option<Vec<String>>,
     #[serde(default)]
     pub subject: Option<String>,
+    #[tabular(name = "CREATED", order = 6)]
     #[serde(default)]
     pub created_at: Option<String>,
+    #[tabular(name = "STATUS")]
     #[serde(default)]
     pub last_event: Option<String>,
+    #[tabular(skip)]
+    #[serde(default)]
+    pub scheduled_at: Option<String>,
+    #[tabular(skip)]
+    #[serde(default)]
+    pub cc: Option<Vec<String>>,
+    #[tabular(skip)]
+    #[serde(default)]
+    pub bcc: Option<Vec<String>>,
+    #[tabular(skip)]
+    #[serde(default)]
+    pub reply_to: Option<Vec<String>>,
+    #[tabular(skip)]
+    #[serde(default)]
+    pub html: Option<String>,
+    #[tabular(skip)]
+    #[serde(default)]
+    pub text: Option<String>,
+    #[tabular(skip)]
+    #[serde(default)]
+    pub events: Option<Vec<EmailEvent>>,
 }
 
-impl Tabular for Email {
-    fn headers() -> Vec<&'static str> {
-        vec!["ID", "TO", "SUBJECT", "STATUS", "CREATED"]
-    }
-
-    fn row(&self) -> Vec<String> {
-        vec![
-            self.id.clone(),
-            self.to.as_ref().map(|v| v.join(", ")).unwrap_or_default(),
-            self.subject.clone().unwrap_or_default(),
-            self.last_event.clone().unwrap_or_default(),
-            self.created_at.clone().unwrap_or_default(),
-        ]
-    }
+/// One step in an email's delivery history, e.g. `sent` or `delivered`
+#[derive(Debug, Clone, Deserialize, Serialize, Tabular)]
+pub struct EmailEvent {
+    #[tabular(name = "EVENT")]
+    #[serde(rename = "type", alias = "event")]
+    pub event: String,
+    #[tabular(name = "AT")]
+    #[serde(default, alias = "timestamp")]
+    pub created_at: Option<String>,
 }
 
+/// Envelope of `GET /emails`: one page of results
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct EmailsResponse {
     pub data: Vec<Email>,
+    /// Whether another page follows this one
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+impl EmailsResponse {
+    /// The `after` cursor for the next page, if there is one
+    pub fn next_cursor(&self) -> Option<&str> {
+        if !self.has_more {
+            return None;
+        }
+        self.data.last().map(|email| email.id.as_str())
+    }
+}
+
+/// Delivery status of an email, as reported in `last_event`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum EmailStatus {
+    Queued,
+    Scheduled,
+    Sent,
+    Delivered,
+    DeliveryDelayed,
+    Opened,
+    Clicked,
+    Bounced,
+    Complained,
+    Canceled,
+    Failed,
+}
+
+impl EmailStatus {
+    /// The status string as returned by the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailStatus::Queued => "queued",
+            EmailStatus::Scheduled => "scheduled",
+            EmailStatus::Sent => "sent",
+            EmailStatus::Delivered => "delivered",
+            EmailStatus::DeliveryDelayed => "delivery_delayed",
+            EmailStatus::Opened => "opened",
+            EmailStatus::Clicked => "clicked",
+            EmailStatus::Bounced => "bounced",
+            EmailStatus::Complained => "complained",
+            EmailStatus::Canceled => "canceled",
+            EmailStatus::Failed => "failed",
+        }
+    }
 }
 
 // === Domain Types ===
 
+/// Domain verification status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DomainStatus {
+    Verified,
+    Pending,
+    Failed,
+    #[value(name = "not_started")]
+    NotStarted,
+}
+
+impl DomainStatus {
+    /// The status string as returned by the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DomainStatus::Verified => "verified",
+            DomainStatus::Pending => "pending",
+            DomainStatus::Failed => "failed",
+            DomainStatus::NotStarted => "not_started",
+        }
+    }
+}
+
+/// Supported sending regions; backs both `--region` validation and `domains regions`
+pub const REGIONS: &[Region] = &[
+    Region {
+        name: "us-east-1",
+        location: "North Virginia, USA",
+    },
+    Region {
+        name: "eu-west-1",
+        location: "Ireland",
+    },
+    Region {
+        name: "sa-east-1",
+        location: "São Paulo, Brazil",
+    },
+    Region {
+        name: "ap-northeast-1",
+        location: "Tokyo, Japan",
+    },
+];
+
+/// A region a domain can send from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Tabular)]
+pub struct Region {
+    #[tabular(name = "REGION")]
+    pub name: &'static str,
+    pub location: &'static str,
+}
+
+impl ValueEnum for Region {
+    fn value_variants<'a>() -> &'a [Self] {
+        REGIONS
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(self.name).help(self.location))
+    }
+}
+
+/// Body of `POST /domains`
 #[derive(Debug, Serialize)]
 pub struct CreateDomainRequest {
     pub name: String,
@@ -94,6 +396,19 @@ pub struct CreateDomainRequest {
     pub region: Option<String>,
 }
 
+/// TLS policy for outgoing mail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TlsMode {
+    /// Use TLS when the receiving server supports it
+    Opportunistic,
+    /// Only deliver over TLS; fail otherwise
+    Enforced,
+}
+
+/// Settings that can be changed on an existing domain
+///
+/// These are the only fields the API accepts on `PATCH /domains/{id}`.
 #[derive(Debug, Serialize)]
 pub struct UpdateDomainRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -101,10 +416,18 @@ pub struct UpdateDomainRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub open_tracking: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tls: Option<String>,
+    pub tls: Option<TlsMode>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+impl UpdateDomainRequest {
+    /// True when no field would be sent
+    pub fn is_empty(&self) -> bool {
+        self.click_tracking.is_none() && self.open_tracking.is_none() && self.tls.is_none()
+    }
+}
+
+/// A sending domain and, when fetched by ID, its DNS records
+#[derive(Debug, Clone, Deserialize, Serialize, Tabular)]
 pub struct Domain {
     pub id: String,
     pub name: String,
@@ -112,10 +435,12 @@ pub struct Domain {
     pub status: Option<String>,
     #[serde(default)]
     pub region: Option<String>,
+    #[tabular(skip)]
     #[serde(default)]
     pub records: Option<Vec<DnsRecord>>,
 }
 
+/// A DNS record the domain needs for verification
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct DnsRecord {
     pub record: String,
@@ -131,21 +456,7 @@ pub struct DnsRecord {
     pub priority: Option<i32>,
 }
 
-impl Tabular for Domain {
-    fn headers() -> Vec<&'static str> {
-        vec!["ID", "NAME", "STATUS", "REGION"]
-    }
-
-    fn row(&self) -> Vec<String> {
-        vec![
-            self.id.clone(),
-            self.name.clone(),
-            self.status.clone().unwrap_or_default(),
-            self.region.clone().unwrap_or_default(),
-        ]
-    }
-}
-
+/// Envelope of `GET /domains`
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct DomainsResponse {
     pub data: Vec<Domain>,
@@ -162,33 +473,192 @@ pub struct CreateApiKeyRequest {
     pub domain_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// An API key; `token` is only returned when the key is created
+#[derive(Debug, Clone, Deserialize, Serialize, Tabular)]
 pub struct ApiKey {
     pub id: String,
     pub name: String,
+    #[tabular(skip)]
     #[serde(default)]
     pub token: Option<String>,
+    #[tabular(name = "CREATED")]
     #[serde(default)]
     pub created_at: Option<String>,
 }
 
-impl Tabular for ApiKey {
-    fn headers() -> Vec<&'static str> {
-        vec!["ID", "NAME", "CREATED"]
+/// Envelope of `GET /api-keys`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApiKeysResponse {
+    pub data: Vec<ApiKey>,
+}
+
+// === Audience Types ===
+
+/// Body of `POST /audiences`
+#[derive(Debug, Serialize)]
+pub struct CreateAudienceRequest {
+    pub name: String,
+}
+
+/// A named list of contacts
+#[derive(Debug, Clone, Deserialize, Serialize, Tabular)]
+pub struct Audience {
+    pub id: String,
+    pub name: String,
+    #[tabular(name = "CREATED")]
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// Envelope of `GET /audiences`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AudiencesResponse {
+    pub data: Vec<Audience>,
+}
+
+// === Contact Types ===
+
+/// Body of `POST /audiences/{id}/contacts`
+#[derive(Debug, Serialize)]
+pub struct CreateContactRequest {
+    pub email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unsubscribed: Option<bool>,
+}
+
+/// Body of `PATCH /audiences/{id}/contacts/{id}`; only set fields change
+#[derive(Debug, Serialize)]
+pub struct UpdateContactRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unsubscribed: Option<bool>,
+}
+
+impl UpdateContactRequest {
+    /// True when no field would be sent
+    pub fn is_empty(&self) -> bool {
+        self.email.is_none()
+            && self.first_name.is_none()
+            && self.last_name.is_none()
+            && self.unsubscribed.is_none()
     }
+}
 
-    fn row(&self) -> Vec<String> {
-        vec![
-            self.id.clone(),
-            self.name.clone(),
-            self.created_at.clone().unwrap_or_default(),
-        ]
+/// A contact in an audience
+#[derive(Debug, Clone, Deserialize, Serialize, Tabular)]
+pub struct Contact {
+    pub id: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[tabular(name = "FIRST NAME")]
+    #[serde(default)]
+    pub first_name: Option<String>,
+    #[tabular(name = "LAST NAME")]
+    #[serde(default)]
+    pub last_name: Option<String>,
+    #[serde(default)]
+    pub unsubscribed: bool,
+    #[tabular(name = "CREATED")]
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// Outcome of one row of a `contacts import`
+#[derive(Debug, Clone, Serialize, Tabular)]
+pub struct ImportResult {
+    pub line: u64,
+    pub email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[tabular(name = "RESULT", default = "created")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Envelope of `GET /audiences/{id}/contacts`: one page of results
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ContactsResponse {
+    pub data: Vec<Contact>,
+    /// Whether another page follows this one
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+impl ContactsResponse {
+    /// The `after` cursor for the next page, if there is one
+    pub fn next_cursor(&self) -> Option<&str> {
+        if !self.has_more {
+            return None;
+        }
+        self.data.last().map(|contact| contact.id.as_str())
     }
 }
 
+// === Broadcast Types ===
+
+/// Body of `POST /broadcasts`
+#[derive(Debug, Serialize)]
+pub struct CreateBroadcastRequest {
+    pub audience_id: String,
+    pub from: String,
+    pub subject: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+/// Body of `POST /broadcasts/{id}/send`
+#[derive(Debug, Serialize)]
+pub struct SendBroadcastRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheduled_at: Option<String>,
+}
+
+/// An email sent to every contact in an audience
+#[derive(Debug, Clone, Deserialize, Serialize, Tabular)]
+pub struct Broadcast {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub subject: Option<String>,
+    #[tabular(skip)]
+    #[serde(default)]
+    pub audience_id: Option<String>,
+    #[tabular(skip)]
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[tabular(name = "CREATED")]
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[tabular(skip)]
+    #[serde(default)]
+    pub scheduled_at: Option<String>,
+    #[tabular(skip)]
+    #[serde(default)]
+    pub sent_at: Option<String>,
+}
+
+/// Envelope of `GET /broadcasts`
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct ApiKeysResponse {
-    pub data: Vec<ApiKey>,
+pub struct BroadcastsResponse {
+    pub data: Vec<Broadcast>,
 }
 
 // === Template Types ===
@@ -203,6 +673,7 @@ pub struct CreateTemplateRequest {
     pub text: Option<String>,
 }
 
+/// Body of `PATCH /templates/{id}`; only the given fields change
 #[derive(Debug, Serialize)]
 pub struct UpdateTemplateRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -215,36 +686,67 @@ pub struct UpdateTemplateRequest {
     pub text: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// A stored email template
+#[derive(Debug, Clone, Deserialize, Serialize, Tabular)]
 pub struct Template {
     pub id: String,
     pub name: String,
     #[serde(default)]
     pub subject: Option<String>,
+    #[tabular(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    #[tabular(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[tabular(name = "CREATED")]
     #[serde(default)]
     pub created_at: Option<String>,
 }
 
-impl Tabular for Template {
-    fn headers() -> Vec<&'static str> {
-        vec!["ID", "NAME", "SUBJECT", "CREATED"]
-    }
-
-    fn row(&self) -> Vec<String> {
-        vec![
-            self.id.clone(),
-            self.name.clone(),
-            self.subject.clone().unwrap_or_default(),
-            self.created_at.clone().unwrap_or_default(),
-        ]
-    }
-}
-
+/// Envelope of `GET /templates`
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TemplatesResponse {
     pub data: Vec<Template>,
 }
 
+/// A placeholder used by a template and the parts it appears in
+#[derive(Debug, Clone, Serialize, Tabular)]
+pub struct TemplateVariable {
+    #[tabular(name = "VARIABLE")]
+    pub name: String,
+    pub found_in: Vec<String>,
+}
+
+/// A problem found by `templates validate`
+#[derive(Debug, Clone, Serialize, Tabular)]
+pub struct TemplateIssue {
+    pub part: String,
+    pub line: usize,
+    pub issue: String,
+}
+
+// === Key Permission ===
+
+/// Permission level of an API key, inferred by probing the API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyPermission {
+    FullAccess,
+    SendingAccess,
+    Invalid,
+}
+
+impl KeyPermission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyPermission::FullAccess => "full_access",
+            KeyPermission::SendingAccess => "sending_access",
+            KeyPermission::Invalid => "invalid",
+        }
+    }
+}
+
 // === Error Response ===
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -254,3 +756,166 @@ pub struct ErrorResponse {
     #[serde(default)]
     pub name: Option<String>,
 }
+
+/// An email with only its ID set, for tests to fill in with struct update
+/// syntax: `Email { subject: Some(..), ..Email::fixture("email-1") }`
+#[cfg(test)]
+impl Email {
+    pub fn fixture(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            from: None,
+            to: None,
+            subject: None,
+            created_at: None,
+            last_event: None,
+            scheduled_at: None,
+            cc: None,
+            bcc: None,
+            reply_to: None,
+            html: None,
+            text: None,
+            events: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derived_columns_follow_attributes() {
+        let email = Email {
+            from: Some("me@example.com".to_string()),
+            to: Some(vec![
+                "a@example.com".to_string(),
+                "b@example.com".to_string(),
+            ]),
+            created_at: Some("2025-01-01".to_string()),
+            last_event: Some("delivered".to_string()),
+            ..Email::fixture("email-1")
+        };
+        assert_eq!(
+            Email::headers(),
+            vec!["ID", "TO", "SUBJECT", "STATUS", "CREATED"]
+        );
+        assert_eq!(
+            email.row(),
+            vec![
+                "email-1",
+                "a@example.com, b@example.com",
+                "",
+                "delivered",
+                "2025-01-01"
+            ]
+        );
+        assert_eq!(email.id_value().as_deref(), Some("email-1"));
+    }
+
+    #[test]
+    fn test_derived_default_for_missing_value() {
+        let result = MergeResult {
+            line: 2,
+            to: "a@example.com".to_string(),
+            id: Some("email-1".to_string()),
+            error: None,
+        };
+        assert_eq!(MergeResult::headers(), vec!["LINE", "TO", "ID", "RESULT"]);
+        assert_eq!(result.row(), vec!["2", "a@example.com", "email-1", "sent"]);
+    }
+
+    #[test]
+    fn test_email_events_deserialize() {
+        let email: Email = serde_json::from_value(serde_json::json!({
+            "id": "email-1",
+            "last_event": "delivered",
+            "events": [
+                {"type": "sent", "created_at": "2025-01-15 10:00:00.000+00"},
+                {"event": "delivered", "timestamp": "2025-01-15 10:00:02.000+00"}
+            ]
+        }))
+        .unwrap();
+
+        let events = email.events.unwrap();
+        assert_eq!(EmailEvent::headers(), vec!["EVENT", "AT"]);
+        assert_eq!(events[0].row(), vec!["sent", "2025-01-15 10:00:00.000+00"]);
+        assert_eq!(events[1].event, "delivered");
+        assert_eq!(
+            serde_json::to_value(&events[1]).unwrap(),
+            serde_json::json!({"type": "delivered", "created_at": "2025-01-15 10:00:02.000+00"})
+        );
+    }
+
+    #[test]
+    fn test_broadcast_columns() {
+        let broadcast: Broadcast = serde_json::from_value(serde_json::json!({
+            "id": "bc-1",
+            "audience_id": "aud-1",
+            "status": "draft",
+            "created_at": "2024-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        assert_eq!(
+            Broadcast::headers(),
+            vec!["ID", "NAME", "SUBJECT", "STATUS", "CREATED"]
+        );
+        assert_eq!(
+            broadcast.row(),
+            vec!["bc-1", "", "", "draft", "2024-01-01T00:00:00Z"]
+        );
+    }
+
+    #[test]
+    fn test_contact_columns() {
+        let contact: Contact = serde_json::from_value(serde_json::json!({
+            "id": "contact-1",
+            "email": "ada@example.com",
+            "first_name": "Ada",
+            "unsubscribed": true
+        }))
+        .unwrap();
+        assert_eq!(
+            Contact::headers(),
+            vec![
+                "ID",
+                "EMAIL",
+                "FIRST NAME",
+                "LAST NAME",
+                "UNSUBSCRIBED",
+                "CREATED"
+            ]
+        );
+        assert_eq!(
+            contact.row(),
+            vec!["contact-1", "ada@example.com", "Ada", "", "true", ""]
+        );
+    }
+
+    #[test]
+    fn test_mixed_attachments_serialize_path_or_content() {
+        let attachments = vec![
+            Attachment {
+                filename: Some("notes.txt".to_string()),
+                path: None,
+                content: Some("aGVsbG8=".to_string()),
+                content_id: None,
+                disposition: None,
+            },
+            Attachment {
+                filename: None,
+                path: Some("https://cdn.example.com/report.pdf".to_string()),
+                content: None,
+                content_id: None,
+                disposition: None,
+            },
+        ];
+        assert_eq!(
+            serde_json::to_value(&attachments).unwrap(),
+            serde_json::json!([
+                {"filename": "notes.txt", "content": "aGVsbG8="},
+                {"path": "https://cdn.example.com/report.pdf"}
+            ])
+        );
+    }
+}
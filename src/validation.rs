@@ -0,0 +1,94 @@
+// ABOUTME: Local sanity checks on user input before it reaches the API.
+// ABOUTME: Catches malformed email addresses without a network round-trip.
+
+use anyhow::{bail, Result};
+
+/// Check that an address looks like a real email address
+///
+/// Accepts a bare `local@domain` or the display-name form
+/// `Name <local@domain>`. This catches typos, not every RFC 5322 edge case;
+/// the API remains the final judge.
+pub fn validate_email(address: &str) -> Result<()> {
+    if let Err(reason) = check_address(address.trim()) {
+        bail!("Invalid email address '{}': {}", address, reason);
+    }
+    Ok(())
+}
+
+fn check_address(address: &str) -> Result<(), &'static str> {
+    let address = match address.find('<') {
+        Some(open) => address[open + 1..]
+            .strip_suffix('>')
+            .ok_or("missing closing '>'")?,
+        None => address,
+    };
+
+    let (local, domain) = address.rsplit_once('@').ok_or("missing '@'")?;
+    if local.is_empty() {
+        return Err("nothing before '@'");
+    }
+    if domain.is_empty() {
+        return Err("nothing after '@'");
+    }
+    if address.chars().any(char::is_whitespace) {
+        return Err("contains whitespace");
+    }
+    if local.contains('@') {
+        return Err("more than one '@'");
+    }
+    if !domain.contains('.') {
+        return Err("domain has no '.'");
+    }
+    if domain.split('.').any(str::is_empty) {
+        return Err("domain has an empty label");
+    }
+    if !domain
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '.')
+    {
+        return Err("domain has invalid characters");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_email_accepts_common_forms() {
+        for address in [
+            "a@b.com",
+            "first.last+tag@mail.example.co.uk",
+            "Ada Lovelace <ada@example.com>",
+            "\"Lovelace, Ada\" <ada@example.com>",
+            "  padded@example.com ",
+            "user@bücher.de",
+        ] {
+            assert!(validate_email(address).is_ok(), "{}", address);
+        }
+    }
+
+    #[test]
+    fn test_validate_email_rejects_typos() {
+        for (address, reason) in [
+            ("ada.example.com", "missing '@'"),
+            ("@example.com", "nothing before '@'"),
+            ("ada@", "nothing after '@'"),
+            ("ada@@example.com", "more than one '@'"),
+            ("ada@example", "domain has no '.'"),
+            ("ada@example..com", "domain has an empty label"),
+            ("ada@.example.com", "domain has an empty label"),
+            ("ada lovelace@example.com", "contains whitespace"),
+            ("ada@exa_mple.com", "domain has invalid characters"),
+            ("Ada <ada@example.com", "missing closing '>'"),
+            ("Ada <>", "missing '@'"),
+        ] {
+            let err = validate_email(address).unwrap_err().to_string();
+            assert_eq!(
+                err,
+                format!("Invalid email address '{}': {}", address, reason)
+            );
+        }
+    }
+}